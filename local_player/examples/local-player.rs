@@ -0,0 +1,59 @@
+use local_player::{
+    output::AudioOutput,
+    playlist::{Playlist, PlaylistFormat},
+};
+use std::path::PathBuf;
+
+fn main() -> anyhow::Result<()> {
+    let paths = std::env::args()
+        .skip(1)
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+
+    if paths.is_empty() {
+        println!(
+            "Please specify the audio files, music directories or playlist files to play as arguments"
+        );
+        std::process::exit(1);
+    }
+
+    // expand playlist files into the audio files they contain
+    let mut files = Vec::new();
+    for path in paths {
+        if PlaylistFormat::from_path(&path).is_some() {
+            files.extend(Playlist::load(&path)?.file_paths());
+        } else {
+            files.push(path);
+        }
+    }
+
+    let tracks = local_player::scanner::scan_dirs(&files);
+    for track in &tracks {
+        println!("{track}");
+    }
+    let tracks = tracks.into_iter().map(|t| t.path).collect::<Vec<_>>();
+
+    let n_tracks = tracks.len();
+    // `LOCAL_PLAYER_OUTPUT` can be set to `null` or to a WAV file path to play without a sound card
+    let output = match std::env::var("LOCAL_PLAYER_OUTPUT") {
+        Ok(output) if output == "null" => AudioOutput::Null,
+        Ok(path) => AudioOutput::Wav(PathBuf::from(path)),
+        Err(_) => AudioOutput::Default,
+    };
+    let player = local_player::LocalPlayer::with_output(&output)?;
+    let events = player.events();
+    player.start_tracks(tracks, 0)?;
+
+    let mut n_ended = 0;
+    while let Ok(event) = events.recv() {
+        println!("{}", event.args().join(" "));
+        if let local_player::PlayerEvent::EndOfTrack { .. } = event {
+            n_ended += 1;
+            if n_ended == n_tracks {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
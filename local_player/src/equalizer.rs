@@ -0,0 +1,239 @@
+use parking_lot::Mutex;
+use rodio::{ChannelCount, SampleRate, Source, source::SeekError};
+use serde::{Deserialize, Serialize};
+use std::{
+    f32::consts::PI,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+/// The filter type of an equalizer band
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum BandType {
+    /// boosts or cuts frequencies around the band's frequency
+    Peaking,
+    /// boosts or cuts frequencies below the band's frequency
+    LowShelf,
+    /// boosts or cuts frequencies above the band's frequency
+    HighShelf,
+}
+
+/// A band of a parametric equalizer
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Band {
+    #[serde(rename = "type")]
+    pub typ: BandType,
+    /// the band's center (or corner, for shelf filters) frequency in Hz
+    pub frequency: f32,
+    /// the band's gain in dB
+    pub gain: f32,
+    /// the band's quality factor, a higher value gives a narrower band
+    #[serde(default = "default_q")]
+    pub q: f32,
+}
+
+/// The equalizer settings shared between the player and its sources
+#[derive(Debug, Default)]
+pub(crate) struct SharedBands {
+    bands: Mutex<Vec<Band>>,
+    /// increased every time the bands change, used by sources to detect the changes
+    version: AtomicU64,
+}
+
+impl SharedBands {
+    pub fn set(&self, bands: Vec<Band>) {
+        *self.bands.lock() = bands;
+        self.version.fetch_add(1, Ordering::Release);
+    }
+}
+
+/// A source applying a multi-band equalizer to its inner source.
+///
+/// Each band is a biquad filter, using the formulas from the Audio EQ Cookbook.
+/// Changes to the shared bands are applied to the playing source.
+pub(crate) struct Equalizer<S> {
+    inner: S,
+    bands: Arc<SharedBands>,
+    /// the version of the shared bands that the filters are computed from
+    version: u64,
+    filters: Vec<Biquad>,
+    /// the filters' states of each channel
+    states: Vec<Vec<BiquadState>>,
+    /// the channel of the next sample
+    channel: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct BiquadState {
+    z1: f32,
+    z2: f32,
+}
+
+impl<S: Source> Equalizer<S> {
+    pub fn new(inner: S, bands: Arc<SharedBands>) -> Self {
+        let mut equalizer = Self {
+            inner,
+            bands,
+            version: 0,
+            filters: vec![],
+            states: vec![],
+            channel: 0,
+        };
+        equalizer.update_filters();
+        equalizer
+    }
+
+    /// recomputes the filters from the shared bands, resetting the filters' states
+    fn update_filters(&mut self) {
+        self.version = self.bands.version.load(Ordering::Acquire);
+        let sample_rate = self.inner.sample_rate() as f32;
+        self.filters = self
+            .bands
+            .bands
+            .lock()
+            .iter()
+            .filter_map(|band| Biquad::new(band, sample_rate))
+            .collect();
+        self.reset_states();
+    }
+
+    fn reset_states(&mut self) {
+        self.states = vec![
+            vec![BiquadState::default(); self.filters.len()];
+            usize::from(self.inner.channels())
+        ];
+        self.channel = 0;
+    }
+}
+
+impl<S: Source> Iterator for Equalizer<S> {
+    type Item = rodio::Sample;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.inner.next()?;
+        if self.bands.version.load(Ordering::Relaxed) != self.version {
+            self.update_filters();
+        }
+        if self.filters.is_empty() {
+            return Some(sample);
+        }
+
+        let Some(states) = self.states.get_mut(self.channel) else {
+            // the number of channels changed
+            self.reset_states();
+            return Some(sample);
+        };
+        self.channel = (self.channel + 1) % usize::from(self.inner.channels().max(1));
+
+        Some(
+            self.filters
+                .iter()
+                .zip(states)
+                .fold(sample, |x, (filter, state)| filter.process(state, x)),
+        )
+    }
+}
+
+impl<S: Source> Source for Equalizer<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.inner.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.inner.try_seek(pos)?;
+        self.reset_states();
+        Ok(())
+    }
+}
+
+impl Biquad {
+    /// computes the filter of an equalizer band, returning `None` for a band that has no effect
+    fn new(band: &Band, sample_rate: f32) -> Option<Self> {
+        if band.gain == 0.0 || band.q <= 0.0 || band.frequency <= 0.0 {
+            return None;
+        }
+        let frequency = band.frequency.min(sample_rate * 0.45);
+
+        let a = 10f32.powf(band.gain / 40.0);
+        let w0 = 2.0 * PI * frequency / sample_rate;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * band.q);
+
+        let (b0, b1, b2, a0, a1, a2) = match band.typ {
+            BandType::Peaking => (
+                1.0 + alpha * a,
+                -2.0 * cos,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos,
+                1.0 - alpha / a,
+            ),
+            BandType::LowShelf => {
+                let k = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a + 1.0) - (a - 1.0) * cos + k),
+                    2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                    a * ((a + 1.0) - (a - 1.0) * cos - k),
+                    (a + 1.0) + (a - 1.0) * cos + k,
+                    -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                    (a + 1.0) + (a - 1.0) * cos - k,
+                )
+            }
+            BandType::HighShelf => {
+                let k = 2.0 * a.sqrt() * alpha;
+                (
+                    a * ((a + 1.0) + (a - 1.0) * cos + k),
+                    -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                    a * ((a + 1.0) + (a - 1.0) * cos - k),
+                    (a + 1.0) - (a - 1.0) * cos + k,
+                    2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                    (a + 1.0) - (a - 1.0) * cos - k,
+                )
+            }
+        };
+
+        Some(Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        })
+    }
+
+    /// filters a sample, using the transposed direct form II
+    fn process(&self, state: &mut BiquadState, x: f32) -> f32 {
+        let y = self.b0 * x + state.z1;
+        state.z1 = self.b1 * x - self.a1 * y + state.z2;
+        state.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+}
+
+fn default_q() -> f32 {
+    std::f32::consts::FRAC_1_SQRT_2
+}
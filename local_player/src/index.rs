@@ -0,0 +1,513 @@
+use crate::{
+    model::{Album, Artist, Track},
+    scanner::{is_audio_file, is_hidden, list_audio_files, read_track},
+};
+use anyhow::{Context, Result};
+use notify::Watcher;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+/// name of the library index file inside the cache folder
+const INDEX_FILE_NAME: &str = "LocalLibrary_cache.json";
+
+/// duration to wait for more file system events before reporting a batch of changes
+const WATCHER_DEBOUNCE_DURATION: Duration = Duration::from_millis(500);
+
+/// maximum difference between the durations of two matching tracks
+const MAX_DURATION_DIFF: Duration = Duration::from_secs(10);
+
+/// duration difference under which two tracks are considered to have the same duration
+const CLOSE_DURATION_DIFF: Duration = Duration::from_secs(3);
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct IndexEntry {
+    track: Track,
+    /// the audio file's last modification time when the track was read
+    modified: SystemTime,
+    /// the audio file's size when the track was read
+    size: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+/// An index of the tracks in a local music library, keyed by the tracks' file paths
+pub struct LibraryIndex {
+    entries: BTreeMap<PathBuf, IndexEntry>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// Statistics of the changes made to a library index by a scan
+pub struct ScanStats {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+#[derive(Debug, Default)]
+/// Changes to a library index read from the file system, see [`LibraryIndex::read_paths`]
+pub struct IndexChanges {
+    /// the re-read entries of changed files, `None` for files that failed to be read
+    entries: Vec<(PathBuf, Option<IndexEntry>)>,
+    /// the paths of deleted files
+    removed: Vec<PathBuf>,
+}
+
+impl ScanStats {
+    /// whether the scan changed the index
+    #[must_use]
+    pub fn has_changes(&self) -> bool {
+        self.added + self.updated + self.removed > 0
+    }
+}
+
+impl LibraryIndex {
+    /// loads the library index stored in the cache folder, returning an empty index if none found
+    #[must_use]
+    pub fn load(cache_folder: &Path) -> Self {
+        let path = cache_folder.join(INDEX_FILE_NAME);
+        if !path.exists() {
+            return Self::default();
+        }
+
+        tracing::info!("Loading local library index from {}...", path.display());
+        let result = std::fs::File::open(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|f| Ok(serde_json::from_reader(BufReader::new(f))?));
+        match result {
+            Ok(index) => {
+                tracing::info!("Successfully loaded local library index!");
+                index
+            }
+            Err(err) => {
+                tracing::error!("Failed to load local library index: {err:#}");
+                Self::default()
+            }
+        }
+    }
+
+    /// stores the library index into the cache folder
+    pub fn store(&self, cache_folder: &Path) -> Result<()> {
+        let path = cache_folder.join(INDEX_FILE_NAME);
+        let f = BufWriter::new(
+            std::fs::File::create(&path).with_context(|| format!("create {}", path.display()))?,
+        );
+        serde_json::to_writer(f, self).context("serialize local library index")?;
+        Ok(())
+    }
+
+    /// rescans the given music directories.
+    ///
+    /// Only files whose modification time or size changed since the last scan are re-read.
+    /// Entries of files that no longer exist in the music directories are dropped.
+    pub fn rescan(&mut self, dirs: &[PathBuf]) -> ScanStats {
+        let paths = list_audio_files(dirs);
+
+        let mut changes = IndexChanges {
+            entries: vec![],
+            removed: self
+                .entries
+                .keys()
+                .filter(|path| paths.binary_search(path).is_err())
+                .cloned()
+                .collect(),
+        };
+        for path in paths {
+            self.read_file(path, &mut changes);
+        }
+        self.apply_changes(changes)
+    }
+
+    /// updates the index entries of the given paths, which can be either files or directories.
+    ///
+    /// This is used to apply changes reported by a [`LibraryWatcher`].
+    pub fn update_paths(&mut self, paths: &[PathBuf]) -> ScanStats {
+        let changes = self.read_paths(paths);
+        self.apply_changes(changes)
+    }
+
+    /// reads the changes of the given paths, which can be either files or directories,
+    /// without modifying the index.
+    ///
+    /// The changes are applied with [`LibraryIndex::apply_changes`], which allows reading
+    /// the files without holding a lock on the index.
+    #[must_use]
+    pub fn read_paths(&self, paths: &[PathBuf]) -> IndexChanges {
+        let mut changes = IndexChanges::default();
+        for path in paths {
+            if path.is_dir() {
+                for path in list_audio_files(std::slice::from_ref(path)) {
+                    self.read_file(path, &mut changes);
+                }
+            } else if path.is_file() && is_audio_file(path) && !is_hidden(path) {
+                self.read_file(path.clone(), &mut changes);
+            }
+
+            // drop entries of deleted files, including files inside a deleted directory
+            changes.removed.extend(
+                self.entries
+                    .keys()
+                    .filter(|p| p.starts_with(path) && !p.exists())
+                    .cloned(),
+            );
+        }
+        changes
+    }
+
+    /// applies changes read from the file system to the index
+    pub fn apply_changes(&mut self, changes: IndexChanges) -> ScanStats {
+        let mut stats = ScanStats::default();
+        for (path, entry) in changes.entries {
+            match entry {
+                Some(entry) => {
+                    if self.entries.insert(path, entry).is_some() {
+                        stats.updated += 1;
+                    } else {
+                        stats.added += 1;
+                    }
+                }
+                None => {
+                    if self.entries.remove(&path).is_some() {
+                        stats.removed += 1;
+                    }
+                }
+            }
+        }
+        for path in changes.removed {
+            if self.entries.remove(&path).is_some() {
+                stats.removed += 1;
+            }
+        }
+        stats
+    }
+
+    /// re-reads a file if it is not indexed or has changed since it was last read
+    fn read_file(&self, path: PathBuf, changes: &mut IndexChanges) {
+        let metadata = match std::fs::metadata(&path) {
+            Ok(metadata) => metadata,
+            Err(err) => {
+                tracing::warn!("Failed to get metadata of {}: {err:#}", path.display());
+                return;
+            }
+        };
+        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        let size = metadata.len();
+
+        if self
+            .entries
+            .get(&path)
+            .is_some_and(|entry| entry.modified == modified && entry.size == size)
+        {
+            return;
+        }
+
+        match read_track(&path) {
+            Ok(track) => changes.entries.push((
+                path,
+                Some(IndexEntry {
+                    track,
+                    modified,
+                    size,
+                }),
+            )),
+            Err(err) => {
+                tracing::warn!("Failed to read track {}: {err:#}", path.display());
+                changes.entries.push((path, None));
+            }
+        }
+    }
+
+    /// gets the indexed tracks, sorted by their file paths
+    pub fn tracks(&self) -> impl Iterator<Item = &Track> {
+        self.entries.values().map(|entry| &entry.track)
+    }
+
+    /// gets the albums of the indexed tracks, sorted by their names
+    #[must_use]
+    pub fn albums(&self) -> Vec<&Album> {
+        let mut albums = self
+            .tracks()
+            .filter_map(|t| t.album.as_ref())
+            .collect::<Vec<_>>();
+        albums.sort_by(|x, y| (x.name.to_lowercase(), &x.id).cmp(&(y.name.to_lowercase(), &y.id)));
+        albums.dedup_by(|x, y| x.id == y.id);
+        albums
+    }
+
+    /// gets the artists of the indexed tracks, sorted by their names
+    #[must_use]
+    pub fn artists(&self) -> Vec<&Artist> {
+        let mut artists = self.tracks().flat_map(|t| &t.artists).collect::<Vec<_>>();
+        artists.sort_by(|x, y| (x.name.to_lowercase(), &x.id).cmp(&(y.name.to_lowercase(), &y.id)));
+        artists.dedup_by(|x, y| x.id == y.id);
+        artists
+    }
+
+    /// gets the tracks of an album, sorted by their disc and track numbers
+    #[must_use]
+    pub fn album_tracks(&self, album_id: &str) -> Vec<&Track> {
+        let mut tracks = self
+            .tracks()
+            .filter(|t| t.album.as_ref().is_some_and(|a| a.id == album_id))
+            .collect::<Vec<_>>();
+        tracks.sort_by_key(|t| (t.disc_number, t.track_number));
+        tracks
+    }
+
+    /// gets the tracks of an artist, sorted by their albums, disc and track numbers
+    #[must_use]
+    pub fn artist_tracks(&self, artist_id: &str) -> Vec<&Track> {
+        let mut tracks = self
+            .tracks()
+            .filter(|t| t.artists.iter().any(|a| a.id == artist_id))
+            .collect::<Vec<_>>();
+        tracks.sort_by_key(|t| (t.album_info().to_lowercase(), t.disc_number, t.track_number));
+        tracks
+    }
+
+    /// finds the indexed track that best matches the given track metadata,
+    /// e.g. the metadata of a local file entry in a Spotify playlist.
+    ///
+    /// A matching track must have the same title, ignoring case. Tracks whose artists or duration
+    /// clearly differ are rejected, and the remaining tracks are ranked by matching artists, album
+    /// and duration. Empty artists, album or a zero duration are treated as unknown.
+    #[must_use]
+    pub fn find_track(
+        &self,
+        name: &str,
+        artists: &[&str],
+        album: &str,
+        duration: Duration,
+    ) -> Option<&Track> {
+        let name = normalize(name);
+        let artists = artists.iter().map(|a| normalize(a)).collect::<Vec<_>>();
+        let album = normalize(album);
+
+        self.tracks()
+            .filter(|t| normalize(&t.name) == name)
+            .filter_map(|t| {
+                let mut score = 0;
+
+                if !artists.is_empty() && !t.artists.is_empty() {
+                    if !t
+                        .artists
+                        .iter()
+                        .any(|a| artists.contains(&normalize(&a.name)))
+                    {
+                        return None;
+                    }
+                    score += 1;
+                }
+
+                if !album.is_empty()
+                    && t.album
+                        .as_ref()
+                        .is_some_and(|a| normalize(&a.name) == album)
+                {
+                    score += 1;
+                }
+
+                if !duration.is_zero() && !t.duration.is_zero() {
+                    let diff = duration.abs_diff(t.duration);
+                    if diff > MAX_DURATION_DIFF {
+                        return None;
+                    }
+                    if diff <= CLOSE_DURATION_DIFF {
+                        score += 1;
+                    }
+                }
+
+                Some((score, t))
+            })
+            // prefer the first track (by path) among equally ranked tracks
+            .min_by_key(|(score, _)| std::cmp::Reverse(*score))
+            .map(|(_, t)| t)
+    }
+
+    /// gets the track of an audio file
+    #[must_use]
+    pub fn track(&self, path: &Path) -> Option<&Track> {
+        self.entries.get(path).map(|entry| &entry.track)
+    }
+
+    /// gets the number of indexed tracks
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// whether the index has no track
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// A watcher that reports changes inside music directories using the OS's file system
+/// notifications (e.g. inotify on Linux)
+pub struct LibraryWatcher {
+    event_rx: flume::Receiver<notify::Result<notify::Event>>,
+    // the watcher needs to be kept alive to receive file system events
+    _watcher: notify::RecommendedWatcher,
+}
+
+impl LibraryWatcher {
+    /// creates a watcher that recursively watches the given music directories
+    pub fn new(dirs: &[PathBuf]) -> Result<Self> {
+        let (event_tx, event_rx) = flume::unbounded();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            event_tx.send(event).unwrap_or_default();
+        })
+        .context("create file system watcher")?;
+
+        for dir in dirs {
+            watcher
+                .watch(dir, notify::RecursiveMode::Recursive)
+                .with_context(|| format!("watch {}", dir.display()))?;
+        }
+
+        Ok(Self {
+            event_rx,
+            _watcher: watcher,
+        })
+    }
+
+    /// blocks until some paths inside the music directories change, returning the changed paths.
+    ///
+    /// Events that happen in a short period are batched together.
+    /// Returns `None` if the watcher stopped.
+    #[must_use]
+    pub fn next_changes(&self) -> Option<Vec<PathBuf>> {
+        let mut paths = Vec::new();
+        loop {
+            add_event_paths(&mut paths, self.event_rx.recv().ok()?);
+            while let Ok(event) = self.event_rx.recv_timeout(WATCHER_DEBOUNCE_DURATION) {
+                add_event_paths(&mut paths, event);
+            }
+            if !paths.is_empty() {
+                break;
+            }
+        }
+
+        paths.sort();
+        paths.dedup();
+        Some(paths)
+    }
+}
+
+/// adds the paths changed by a file system event
+fn add_event_paths(paths: &mut Vec<PathBuf>, event: notify::Result<notify::Event>) {
+    match event {
+        Ok(event) if !event.kind.is_access() => paths.extend(event.paths),
+        Ok(_) => {}
+        Err(err) => tracing::warn!("Failed to watch the music directories: {err:#}"),
+    }
+}
+
+/// normalizes a name for case-insensitive comparisons
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::tests::TempDir;
+
+    fn names(index: &LibraryIndex) -> Vec<&str> {
+        index.tracks().map(|t| t.name.as_str()).collect()
+    }
+
+    fn stats(added: usize, updated: usize, removed: usize) -> ScanStats {
+        ScanStats {
+            added,
+            updated,
+            removed,
+        }
+    }
+
+    #[test]
+    fn rescan_changed_files() {
+        let dir = TempDir::new("index_rescan");
+        let dirs = [dir.0.clone()];
+        let a = dir.wav("a.wav", Duration::from_secs(1), &[(*b"INAM", "A")]);
+
+        let mut index = LibraryIndex::default();
+        assert_eq!(index.rescan(&dirs), stats(1, 0, 0));
+        assert_eq!(names(&index), ["A"]);
+
+        // unchanged files are not read again
+        index.entries.get_mut(&a).unwrap().track.name = "indexed A".to_string();
+        assert_eq!(index.rescan(&dirs), stats(0, 0, 0));
+        assert_eq!(names(&index), ["indexed A"]);
+
+        // a modified file is read again
+        dir.wav("a.wav", Duration::from_secs(2), &[(*b"INAM", "New A")]);
+        dir.wav("b.wav", Duration::from_secs(1), &[(*b"INAM", "B")]);
+        assert_eq!(index.rescan(&dirs), stats(1, 1, 0));
+        assert_eq!(names(&index), ["New A", "B"]);
+        assert_eq!(index.track(&a).unwrap().duration, Duration::from_secs(2));
+
+        // deleted files and files that can no longer be read are removed
+        std::fs::remove_file(&a).unwrap();
+        std::fs::write(dir.0.join("b.wav"), b"not an audio file").unwrap();
+        std::fs::write(dir.0.join("c.mp3"), b"not an audio file").unwrap();
+        assert_eq!(index.rescan(&dirs), stats(0, 0, 2));
+        assert!(index.is_empty());
+    }
+
+    #[test]
+    fn update_changed_paths() {
+        let dir = TempDir::new("index_update");
+        let album = dir.0.join("album");
+        dir.wav("album/a.wav", Duration::from_secs(1), &[(*b"INAM", "A")]);
+        let b = dir.wav("b.wav", Duration::from_secs(1), &[(*b"INAM", "B")]);
+
+        let mut index = LibraryIndex::default();
+        assert_eq!(
+            index.update_paths(std::slice::from_ref(&album)),
+            stats(1, 0, 0)
+        );
+        assert_eq!(index.update_paths(std::slice::from_ref(&b)), stats(1, 0, 0));
+        assert_eq!(names(&index), ["A", "B"]);
+
+        // the index is unchanged until the read changes are applied
+        std::fs::remove_dir_all(&album).unwrap();
+        let changes = index.read_paths(&[album, dir.0.join("missing.wav")]);
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.apply_changes(changes), stats(0, 0, 1));
+        assert_eq!(names(&index), ["B"]);
+    }
+
+    #[test]
+    fn load_stored_index() {
+        let dir = TempDir::new("index_load");
+        let music = dir.0.join("music");
+        let a = dir.wav("music/a.wav", Duration::from_secs(1), &[(*b"INAM", "A")]);
+        dir.wav("music/b.wav", Duration::from_secs(1), &[(*b"INAM", "B")]);
+
+        let mut index = LibraryIndex::default();
+        index.rescan(std::slice::from_ref(&music));
+        index.store(&dir.0).unwrap();
+
+        let mut index = LibraryIndex::load(&dir.0);
+        assert_eq!(names(&index), ["A", "B"]);
+        // entries of files deleted since the index was stored are dropped by a rescan
+        std::fs::remove_file(&a).unwrap();
+        assert_eq!(index.rescan(std::slice::from_ref(&music)), stats(0, 0, 1));
+        assert_eq!(names(&index), ["B"]);
+
+        // a corrupt or outdated index is ignored
+        for content in [
+            "{\"entries\":",
+            r#"{"entries":{"/music/a.wav":{"track":{}}}}"#,
+        ] {
+            std::fs::write(dir.0.join(INDEX_FILE_NAME), content).unwrap();
+            assert!(LibraryIndex::load(&dir.0).is_empty());
+        }
+        assert!(LibraryIndex::load(&dir.0.join("missing")).is_empty());
+    }
+}
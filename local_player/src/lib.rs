@@ -0,0 +1,24 @@
+//! A local audio player for `spotify_player`, built on top of [`rodio`].
+//!
+//! The crate exposes a [`LocalPlayer`] that manages a queue of audio files on disk
+//! and reports its state changes through a channel of [`PlayerEvent`]s.
+//! Local music libraries can be read with the functions in [`scanner`],
+//! which produce [`model`] structs similar to the ones used for Spotify items.
+//! Scanned libraries are persisted and kept up-to-date with an [`index::LibraryIndex`].
+//! Playlist files (M3U and PLS) can be loaded and saved with [`playlist::Playlist`].
+//! Tracks can be normalized with their [`replay_gain::ReplayGain`] tags and shaped by an equalizer
+//! made of [`equalizer::Band`]s.
+//! Besides the default audio device, the player can play to a WAV file or to no device at all
+//! (see [`output::AudioOutput`]), which allows running it on machines without a sound card.
+
+pub mod equalizer;
+pub mod index;
+pub mod model;
+pub mod output;
+mod player;
+pub mod playlist;
+pub mod replay_gain;
+pub mod scanner;
+mod source;
+
+pub use player::{LocalPlayer, PlayerEvent};
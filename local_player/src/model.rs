@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, time::Duration};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+/// A local track
+pub struct Track {
+    /// path to the track's audio file
+    pub path: PathBuf,
+    pub name: String,
+    pub artists: Vec<Artist>,
+    pub album: Option<Album>,
+    pub track_number: Option<u32>,
+    pub disc_number: Option<u32>,
+    pub duration: Duration,
+    pub year: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+/// A local album
+pub struct Album {
+    pub id: String,
+    pub release_date: String,
+    pub name: String,
+    pub artists: Vec<Artist>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+/// A local artist
+pub struct Artist {
+    pub id: String,
+    pub name: String,
+}
+
+impl Artist {
+    /// creates a local artist, whose id is derived from the artist's name
+    #[must_use]
+    pub fn new(name: String) -> Self {
+        Self {
+            id: format!("local:artist:{}", name.to_lowercase()),
+            name,
+        }
+    }
+}
+
+impl Album {
+    /// creates a local album, whose id is derived from the album's name and artists
+    #[must_use]
+    pub fn new(name: String, artists: Vec<Artist>, release_date: String) -> Self {
+        let artist_names = artists
+            .iter()
+            .map(|a| a.name.to_lowercase())
+            .collect::<Vec<_>>()
+            .join(",");
+        Self {
+            id: format!("local:album:{artist_names}:{}", name.to_lowercase()),
+            release_date,
+            name,
+            artists,
+        }
+    }
+}
+
+impl Track {
+    /// gets the track's artists information
+    #[must_use]
+    pub fn artists_info(&self) -> String {
+        self.artists
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// gets the track's album information
+    #[must_use]
+    pub fn album_info(&self) -> String {
+        self.album
+            .as_ref()
+            .map(|a| a.name.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl std::fmt::Display for Track {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} • {} ▎ {}",
+            self.name,
+            self.artists_info(),
+            self.album_info(),
+        )
+    }
+}
@@ -0,0 +1,195 @@
+use anyhow::{Context, Result};
+use rodio::{
+    ChannelCount, OutputStream, OutputStreamBuilder, SampleRate,
+    mixer::{Mixer, MixerSource},
+};
+use std::{
+    fs::File,
+    io::{BufWriter, Seek, SeekFrom, Write},
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+/// the sample rate of the headless outputs
+const SAMPLE_RATE: SampleRate = 44100;
+/// the number of channels of the headless outputs
+const CHANNELS: ChannelCount = 2;
+/// the interval at which a headless output consumes the played samples
+const TICK: Duration = Duration::from_millis(10);
+
+/// The backend that the local player sends its audio to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AudioOutput {
+    /// the system's default audio output device
+    #[default]
+    Default,
+    /// a WAV file, written in real time as the audio is played
+    Wav(PathBuf),
+    /// no output, the audio is discarded in real time
+    Null,
+}
+
+/// An opened audio output, which plays the audio of its mixer until it is dropped
+pub(crate) enum OutputHandle {
+    Device(OutputStream),
+    Headless(HeadlessOutput),
+}
+
+impl OutputHandle {
+    pub fn open(output: &AudioOutput) -> Result<Self> {
+        match output {
+            AudioOutput::Default => Ok(Self::Device(
+                OutputStreamBuilder::open_default_stream()
+                    .context("open default audio output stream")?,
+            )),
+            AudioOutput::Wav(path) => {
+                let writer = WavWriter::create(path)
+                    .with_context(|| format!("create WAV output {}", path.display()))?;
+                HeadlessOutput::start(Some(writer)).map(Self::Headless)
+            }
+            AudioOutput::Null => HeadlessOutput::start(None).map(Self::Headless),
+        }
+    }
+
+    pub fn mixer(&self) -> &Mixer {
+        match self {
+            Self::Device(stream) => stream.mixer(),
+            Self::Headless(output) => &output.mixer,
+        }
+    }
+}
+
+/// An output without an audio device, consuming the mixer's samples at the playback rate
+/// in a separate thread, so that the playback advances as if it was played by a device
+pub(crate) struct HeadlessOutput {
+    mixer: Mixer,
+    stopped: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl HeadlessOutput {
+    fn start(writer: Option<WavWriter>) -> Result<Self> {
+        let (mixer, source) = rodio::mixer::mixer(CHANNELS, SAMPLE_RATE);
+        let stopped = Arc::new(AtomicBool::new(false));
+        let thread = std::thread::Builder::new()
+            .name("local-player-output".to_string())
+            .spawn({
+                let stopped = stopped.clone();
+                move || {
+                    if let Err(err) = play_headless(source, writer, &stopped) {
+                        tracing::error!("Failed to write the local player's output: {err:#}");
+                    }
+                }
+            })
+            .context("spawn local player output thread")?;
+
+        Ok(Self {
+            mixer,
+            stopped,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for HeadlessOutput {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.join().unwrap_or_default();
+        }
+    }
+}
+
+fn play_headless(
+    mut source: MixerSource,
+    mut writer: Option<WavWriter>,
+    stopped: &AtomicBool,
+) -> Result<()> {
+    let start = Instant::now();
+    let samples_per_sec = u64::from(SAMPLE_RATE) * u64::from(CHANNELS);
+    let mut played: u64 = 0;
+    let mut buffer = Vec::new();
+
+    while !stopped.load(Ordering::Relaxed) {
+        // consume the samples due since the output started, in whole frames
+        let due = (start.elapsed().as_secs_f64() * samples_per_sec as f64) as u64;
+        let due = due - due % u64::from(CHANNELS);
+        buffer.clear();
+        // the mixer has no samples when nothing is playing, which is played as silence
+        buffer.extend((played..due).map(|_| source.next().unwrap_or_default()));
+        played = due;
+
+        if let Some(writer) = &mut writer {
+            writer.write(&buffer)?;
+        }
+        std::thread::sleep(TICK);
+    }
+    Ok(())
+}
+
+/// A writer of 32-bit float WAV files.
+///
+/// The header's sizes are updated after every write, so the file is valid while being written.
+struct WavWriter {
+    file: BufWriter<File>,
+    data_len: u32,
+}
+
+impl WavWriter {
+    /// the size of the header before the audio data
+    const HEADER_LEN: u32 = 44;
+
+    fn create(path: &std::path::Path) -> Result<Self> {
+        let mut writer = Self {
+            file: BufWriter::new(File::create(path)?),
+            data_len: 0,
+        };
+        writer.write_header()?;
+        Ok(writer)
+    }
+
+    fn write_header(&mut self) -> Result<()> {
+        let block_align = CHANNELS * 4;
+        let byte_rate = SAMPLE_RATE * u32::from(block_align);
+
+        let file = &mut self.file;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(b"RIFF")?;
+        file.write_all(
+            &(Self::HEADER_LEN - 8)
+                .saturating_add(self.data_len)
+                .to_le_bytes(),
+        )?;
+        file.write_all(b"WAVEfmt ")?;
+        file.write_all(&16u32.to_le_bytes())?;
+        // the IEEE float format
+        file.write_all(&3u16.to_le_bytes())?;
+        file.write_all(&CHANNELS.to_le_bytes())?;
+        file.write_all(&SAMPLE_RATE.to_le_bytes())?;
+        file.write_all(&byte_rate.to_le_bytes())?;
+        file.write_all(&block_align.to_le_bytes())?;
+        file.write_all(&32u16.to_le_bytes())?;
+        file.write_all(b"data")?;
+        file.write_all(&self.data_len.to_le_bytes())?;
+        Ok(())
+    }
+
+    fn write(&mut self, samples: &[f32]) -> Result<()> {
+        if samples.is_empty() {
+            return Ok(());
+        }
+        self.file.seek(SeekFrom::End(0))?;
+        for sample in samples {
+            self.file.write_all(&sample.to_le_bytes())?;
+        }
+        self.data_len = self.data_len.saturating_add((samples.len() * 4) as u32);
+        self.write_header()?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,703 @@
+use crate::{
+    equalizer::{Band, Equalizer, SharedBands},
+    output::{AudioOutput, OutputHandle},
+    replay_gain::{Normalization, ReplayGain},
+    source::{BoxedSource, CrossfadeSource, SharedSlot, SlotSource},
+};
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use rodio::{
+    Decoder, Sink, Source,
+    source::{EmptyCallback, LimitSettings},
+};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+/// An event emitted by the local player.
+///
+/// The variants mirror the events emitted by the streaming player,
+/// except that a playing item is identified by its file path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayerEvent {
+    Changed { path: PathBuf },
+    Playing { path: PathBuf, position_ms: u32 },
+    Paused { path: PathBuf, position_ms: u32 },
+    EndOfTrack { path: PathBuf },
+}
+
+impl PlayerEvent {
+    /// gets the event's arguments
+    #[must_use]
+    pub fn args(&self) -> Vec<String> {
+        match self {
+            PlayerEvent::Changed { path } => {
+                vec!["Changed".to_string(), path.display().to_string()]
+            }
+            PlayerEvent::Playing { path, position_ms } => vec![
+                "Playing".to_string(),
+                path.display().to_string(),
+                position_ms.to_string(),
+            ],
+            PlayerEvent::Paused { path, position_ms } => vec![
+                "Paused".to_string(),
+                path.display().to_string(),
+                position_ms.to_string(),
+            ],
+            PlayerEvent::EndOfTrack { path } => {
+                vec!["EndOfTrack".to_string(), path.display().to_string()]
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Queue {
+    tracks: Vec<PathBuf>,
+    current: Option<usize>,
+    /// increased every time the sink is reloaded with a new track,
+    /// used to discard end-of-track notifications of stale tracks
+    generation: u64,
+    /// the track appended to the sink after the current track, and the slot passing its source
+    preloaded: Option<(usize, SharedSlot)>,
+    /// the slot that passed the current track's source, if the track was preloaded
+    current_slot: Option<SharedSlot>,
+    /// the slot to pass the source of the track after the last track appended to the sink
+    next_slot: SharedSlot,
+}
+
+/// How the player moves from a track to the next track in the queue
+#[derive(Debug, Clone, Copy)]
+struct Transition {
+    gapless: bool,
+    crossfade: Duration,
+}
+
+struct Inner {
+    sink: Sink,
+    queue: Mutex<Queue>,
+    volume: Mutex<u8>,
+    transition: Mutex<Transition>,
+    normalization: Mutex<Option<Normalization>>,
+    equalizer: Arc<SharedBands>,
+    event_tx: flume::Sender<PlayerEvent>,
+    /// channel to notify the player's worker thread that a track has ended
+    end_tx: flume::Sender<u64>,
+}
+
+/// A player that plays audio files from the local file system
+pub struct LocalPlayer {
+    inner: Arc<Inner>,
+    event_rx: flume::Receiver<PlayerEvent>,
+    // the output needs to be kept alive for the sink to produce sound
+    _output: OutputHandle,
+}
+
+impl LocalPlayer {
+    /// creates a new player connected to the default audio output device
+    pub fn new() -> Result<Self> {
+        Self::with_output(&AudioOutput::Default)
+    }
+
+    /// creates a new player playing to the given audio output
+    pub fn with_output(output: &AudioOutput) -> Result<Self> {
+        let output = OutputHandle::open(output)?;
+        let sink = Sink::connect_new(output.mixer());
+
+        let (event_tx, event_rx) = flume::unbounded();
+        let (end_tx, end_rx) = flume::unbounded();
+
+        let inner = Arc::new(Inner {
+            sink,
+            queue: Mutex::new(Queue::default()),
+            volume: Mutex::new(100),
+            transition: Mutex::new(Transition {
+                gapless: true,
+                crossfade: Duration::ZERO,
+            }),
+            normalization: Mutex::new(None),
+            equalizer: Arc::default(),
+            event_tx,
+            end_tx,
+        });
+
+        let weak = Arc::downgrade(&inner);
+        std::thread::Builder::new()
+            .name("local-player".to_string())
+            .spawn(move || handle_track_end_notifications(&weak, &end_rx))
+            .context("spawn local player thread")?;
+
+        Ok(Self {
+            inner,
+            event_rx,
+            _output: output,
+        })
+    }
+
+    /// returns a receiver of the player's events
+    ///
+    /// Note that cloned receivers share the same channel: each event is delivered to only one receiver.
+    #[must_use]
+    pub fn events(&self) -> flume::Receiver<PlayerEvent> {
+        self.event_rx.clone()
+    }
+
+    /// replaces the player's queue with `tracks` and starts playing the track at `offset`
+    pub fn start_tracks(&self, tracks: Vec<PathBuf>, offset: usize) -> Result<()> {
+        if offset >= tracks.len() {
+            anyhow::bail!(
+                "invalid offset {offset} for a queue of {} tracks",
+                tracks.len()
+            );
+        }
+        // the track is decoded before replacing the queue, so the queue is unchanged if it fails
+        let source = self.inner.load(&tracks[offset])?;
+        let mut queue = self.inner.queue.lock();
+        queue.tracks = tracks;
+        self.inner.sink.clear();
+        self.inner.play_source(&mut queue, offset, source);
+        Ok(())
+    }
+
+    /// adds a track to the end of the player's queue
+    pub fn add_to_queue(&self, path: PathBuf) {
+        let mut queue = self.inner.queue.lock();
+        queue.tracks.push(path);
+        self.inner.preload_next(&mut queue);
+    }
+
+    /// gets the tracks in the player's queue
+    #[must_use]
+    pub fn queue(&self) -> Vec<PathBuf> {
+        self.inner.queue.lock().tracks.clone()
+    }
+
+    /// gets the track currently loaded into the player
+    #[must_use]
+    pub fn current_track(&self) -> Option<PathBuf> {
+        let queue = self.inner.queue.lock();
+        queue.current.map(|id| queue.tracks[id].clone())
+    }
+
+    /// whether the player is playing a track
+    #[must_use]
+    pub fn is_playing(&self) -> bool {
+        !self.inner.sink.is_paused() && !self.inner.sink.empty()
+    }
+
+    /// resumes the playback, restarting the current track if the player was stopped
+    pub fn play(&self) -> Result<()> {
+        let mut queue = self.inner.queue.lock();
+        let Some(id) = queue.current else {
+            return Ok(());
+        };
+        if self.inner.sink.empty() {
+            return self.inner.play_index(&mut queue, id);
+        }
+        self.inner.sink.play();
+        self.inner.send_playback_event(&queue, true);
+        Ok(())
+    }
+
+    /// pauses the playback
+    pub fn pause(&self) {
+        let queue = self.inner.queue.lock();
+        if queue.current.is_none() {
+            return;
+        }
+        self.inner.sink.pause();
+        self.inner.send_playback_event(&queue, false);
+    }
+
+    /// toggles between playing and pausing the playback
+    pub fn resume_pause(&self) -> Result<()> {
+        if self.is_playing() {
+            self.pause();
+            Ok(())
+        } else {
+            self.play()
+        }
+    }
+
+    /// stops the playback, unloading the current track without clearing the queue
+    pub fn stop(&self) {
+        let mut queue = self.inner.queue.lock();
+        queue.generation += 1;
+        queue.preloaded = None;
+        queue.current_slot = None;
+        self.inner.sink.clear();
+        self.inner.send_playback_event(&queue, false);
+    }
+
+    /// plays the next track in the queue
+    pub fn next(&self) -> Result<()> {
+        let mut queue = self.inner.queue.lock();
+        match queue.current {
+            Some(id) if id + 1 < queue.tracks.len() => self.inner.play_index(&mut queue, id + 1),
+            _ => Ok(()),
+        }
+    }
+
+    /// plays the previous track in the queue
+    pub fn previous(&self) -> Result<()> {
+        let mut queue = self.inner.queue.lock();
+        match queue.current {
+            Some(id) if id > 0 => self.inner.play_index(&mut queue, id - 1),
+            _ => Ok(()),
+        }
+    }
+
+    /// seeks the current track to an absolute position
+    pub fn seek(&self, position: Duration) -> Result<()> {
+        let queue = self.inner.queue.lock();
+        if queue.current.is_none() {
+            return Ok(());
+        }
+        self.inner
+            .sink
+            .try_seek(position)
+            .map_err(|err| anyhow::anyhow!("failed to seek: {err}"))?;
+        self.inner
+            .send_playback_event(&queue, !self.inner.sink.is_paused());
+        Ok(())
+    }
+
+    /// gets the playback position of the current track
+    #[must_use]
+    pub fn position(&self) -> Duration {
+        self.inner.position(&self.inner.queue.lock())
+    }
+
+    /// gets the player's volume in percentage
+    #[must_use]
+    pub fn volume(&self) -> u8 {
+        *self.inner.volume.lock()
+    }
+
+    /// sets the player's volume in percentage, capped at 100
+    pub fn set_volume(&self, percent: u8) {
+        let percent = percent.min(100);
+        *self.inner.volume.lock() = percent;
+        self.inner.sink.set_volume(f32::from(percent) / 100.0);
+    }
+
+    /// sets whether the next track in the queue is decoded ahead of time to be played without a gap.
+    ///
+    /// Gapless playback is enabled by default.
+    pub fn set_gapless(&self, gapless: bool) {
+        self.inner.transition.lock().gapless = gapless;
+    }
+
+    /// sets the duration over which the end of a track is faded into the next track in the queue.
+    ///
+    /// A zero duration, the default, disables crossfading. Crossfading implies gapless playback.
+    pub fn set_crossfade(&self, duration: Duration) {
+        self.inner.transition.lock().crossfade = duration;
+    }
+
+    /// sets the loudness normalization applied to tracks loaded from now on, `None` to disable it.
+    ///
+    /// Tracks are normalized using their replay gain tags. Normalization is disabled by default.
+    pub fn set_normalization(&self, normalization: Option<Normalization>) {
+        *self.inner.normalization.lock() = normalization;
+    }
+
+    /// sets the bands of the equalizer applied to the played tracks, including the current track.
+    ///
+    /// An empty list of bands, the default, disables the equalizer.
+    pub fn set_equalizer(&self, bands: Vec<Band>) {
+        self.inner.equalizer.set(bands);
+    }
+}
+
+impl Inner {
+    /// loads the track at position `id` in the queue into the sink and plays it
+    fn play_index(&self, queue: &mut Queue, id: usize) -> Result<()> {
+        let source = self.load(&queue.tracks[id])?;
+        self.sink.clear();
+        self.play_source(queue, id, source);
+        Ok(())
+    }
+
+    /// plays the source of the track at position `id` in the queue, replacing the sink's sources
+    fn play_source(&self, queue: &mut Queue, id: usize, source: BoxedSource) {
+        let path = queue.tracks[id].clone();
+        queue.generation += 1;
+        queue.current = Some(id);
+        queue.preloaded = None;
+        queue.current_slot = None;
+        queue.next_slot = SharedSlot::default();
+
+        self.sink.append(CrossfadeSource::new(
+            source,
+            queue.next_slot.clone(),
+            self.transition.lock().crossfade,
+        ));
+        self.append_end_callback(queue.generation);
+        self.sink.play();
+
+        self.send_event(PlayerEvent::Changed { path });
+        self.send_playback_event(queue, true);
+        self.preload_next(queue);
+    }
+
+    /// decodes the track after the current track and appends it to the sink,
+    /// so that it is played right after the current track ends
+    fn preload_next(&self, queue: &mut Queue) {
+        let transition = *self.transition.lock();
+        if !transition.gapless && transition.crossfade.is_zero() {
+            return;
+        }
+        let Some(id) = queue.current else {
+            return;
+        };
+        if queue.preloaded.is_some() || id + 1 >= queue.tracks.len() || self.sink.empty() {
+            return;
+        }
+
+        let path = &queue.tracks[id + 1];
+        let source = match self.load(path) {
+            Ok(source) => source,
+            Err(err) => {
+                // the track will be loaded again once the current track ends
+                tracing::warn!("Failed to preload the next track: {err:#}");
+                return;
+            }
+        };
+
+        let slot = std::mem::take(&mut queue.next_slot);
+        slot.lock().source = Some(Box::new(CrossfadeSource::new(
+            source,
+            queue.next_slot.clone(),
+            transition.crossfade,
+        )));
+        self.sink.append(SlotSource::new(slot.clone()));
+        self.append_end_callback(queue.generation);
+        queue.preloaded = Some((id + 1, slot));
+    }
+
+    /// decodes a track, applying the equalizer and the loudness normalization
+    fn load(&self, path: &Path) -> Result<BoxedSource> {
+        let source = Equalizer::new(decode(path)?, self.equalizer.clone());
+        let Some(normalization) = *self.normalization.lock() else {
+            return Ok(Box::new(source));
+        };
+
+        let replay_gain = ReplayGain::read(path).unwrap_or_else(|err| {
+            tracing::warn!("Failed to read ReplayGain tags: {err:#}");
+            ReplayGain::default()
+        });
+        let (factor, is_safe) = replay_gain.factor(&normalization);
+        let source = source.amplify(factor);
+        if is_safe {
+            Ok(Box::new(source))
+        } else {
+            // the amplified samples may clip
+            Ok(Box::new(source.limit(LimitSettings::default())))
+        }
+    }
+
+    /// appends a source notifying the end of the previous source to the sink
+    fn append_end_callback(&self, generation: u64) {
+        let end_tx = self.end_tx.clone();
+        self.sink.append(EmptyCallback::new(Box::new(move || {
+            end_tx.send(generation).unwrap_or_default();
+        })));
+    }
+
+    /// gets the playback position of the current track, including the part played during a crossfade
+    fn position(&self, queue: &Queue) -> Duration {
+        let played = queue
+            .current_slot
+            .as_ref()
+            .map_or(Duration::ZERO, |slot| slot.lock().played);
+        self.sink.get_pos() + played
+    }
+
+    fn send_playback_event(&self, queue: &Queue, is_playing: bool) {
+        let Some(id) = queue.current else {
+            return;
+        };
+        let path = queue.tracks[id].clone();
+        let position_ms = self.position(queue).as_millis() as u32;
+        self.send_event(if is_playing {
+            PlayerEvent::Playing { path, position_ms }
+        } else {
+            PlayerEvent::Paused { path, position_ms }
+        });
+    }
+
+    fn send_event(&self, event: PlayerEvent) {
+        // the event is dropped if no one listens to the player's events
+        self.event_tx.send(event).unwrap_or_default();
+    }
+
+    /// handles the end of the track loaded with `generation`, moving to the next track in the queue
+    fn handle_track_end(&self, generation: u64) -> Result<()> {
+        let mut queue = self.queue.lock();
+        if queue.generation != generation {
+            return Ok(());
+        }
+        let Some(id) = queue.current else {
+            return Ok(());
+        };
+
+        self.send_event(PlayerEvent::EndOfTrack {
+            path: queue.tracks[id].clone(),
+        });
+        if let Some((id, slot)) = queue.preloaded.take() {
+            // the preloaded track is already playing
+            queue.current = Some(id);
+            queue.current_slot = Some(slot);
+            self.send_event(PlayerEvent::Changed {
+                path: queue.tracks[id].clone(),
+            });
+            self.send_playback_event(&queue, true);
+            self.preload_next(&mut queue);
+        } else if id + 1 < queue.tracks.len() {
+            // the sink is not cleared because its sources have ended: clearing it would count
+            // the ending end-of-track callback as a source to skip, skipping the next track instead
+            let source = self.load(&queue.tracks[id + 1])?;
+            self.play_source(&mut queue, id + 1, source);
+        }
+        Ok(())
+    }
+}
+
+fn decode(path: &Path) -> Result<Decoder<std::io::BufReader<File>>> {
+    let file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+    Decoder::try_from(file).with_context(|| format!("decode {}", path.display()))
+}
+
+fn handle_track_end_notifications(inner: &Weak<Inner>, end_rx: &flume::Receiver<u64>) {
+    while let Ok(generation) = end_rx.recv() {
+        let Some(inner) = inner.upgrade() else {
+            break;
+        };
+        if let Err(err) = inner.handle_track_end(generation) {
+            tracing::error!("Failed to play the next track: {err:#}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const SAMPLE_RATE: u32 = 44100;
+    const CHANNELS: u16 = 2;
+    /// the amplitude of the fixtures' samples
+    const AMPLITUDE: i16 = i16::MAX / 2;
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// a temporary folder removed when dropped
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("local_player_{name}_{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        /// writes a 16-bit PCM WAV fixture of a constant non-silent signal
+        fn wav(&self, name: &str, duration: Duration) -> PathBuf {
+            let n_frames = (duration.as_secs_f64() * f64::from(SAMPLE_RATE)) as u32;
+            let data_len = n_frames * u32::from(CHANNELS) * 2;
+
+            let mut data = Vec::new();
+            data.extend(b"RIFF");
+            data.extend((36 + data_len).to_le_bytes());
+            data.extend(b"WAVEfmt ");
+            data.extend(16u32.to_le_bytes());
+            data.extend(1u16.to_le_bytes());
+            data.extend(CHANNELS.to_le_bytes());
+            data.extend(SAMPLE_RATE.to_le_bytes());
+            data.extend((SAMPLE_RATE * u32::from(CHANNELS) * 2).to_le_bytes());
+            data.extend((CHANNELS * 2).to_le_bytes());
+            data.extend(16u16.to_le_bytes());
+            data.extend(b"data");
+            data.extend(data_len.to_le_bytes());
+            for _ in 0..n_frames * u32::from(CHANNELS) {
+                data.extend(AMPLITUDE.to_le_bytes());
+            }
+
+            let path = self.0.join(name);
+            std::fs::File::create(&path)
+                .unwrap()
+                .write_all(&data)
+                .unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            std::fs::remove_dir_all(&self.0).unwrap_or_default();
+        }
+    }
+
+    /// receives the player's events until the end of `last`, ignoring the playback positions
+    fn events_until_end(player: &LocalPlayer, last: &Path) -> Vec<PlayerEvent> {
+        let events = player.events();
+        let mut received = vec![];
+        loop {
+            let event = match events.recv_timeout(TIMEOUT).expect("player event") {
+                PlayerEvent::Playing { path, .. } => PlayerEvent::Playing {
+                    path,
+                    position_ms: 0,
+                },
+                PlayerEvent::Paused { path, .. } => PlayerEvent::Paused {
+                    path,
+                    position_ms: 0,
+                },
+                event => event,
+            };
+            let is_last = event
+                == PlayerEvent::EndOfTrack {
+                    path: last.to_path_buf(),
+                };
+            received.push(event);
+            if is_last {
+                return received;
+            }
+        }
+    }
+
+    #[test]
+    fn queue_events_order() {
+        let dir = TempDir::new("events");
+        let a = dir.wav("a.wav", Duration::from_millis(200));
+        let b = dir.wav("b.wav", Duration::from_millis(200));
+
+        for gapless in [true, false] {
+            let player = LocalPlayer::with_output(&AudioOutput::Null).unwrap();
+            player.set_gapless(gapless);
+            player.start_tracks(vec![a.clone(), b.clone()], 0).unwrap();
+
+            let playing = |path: &PathBuf| PlayerEvent::Playing {
+                path: path.clone(),
+                position_ms: 0,
+            };
+            assert_eq!(
+                events_until_end(&player, &b),
+                vec![
+                    PlayerEvent::Changed { path: a.clone() },
+                    playing(&a),
+                    PlayerEvent::EndOfTrack { path: a.clone() },
+                    PlayerEvent::Changed { path: b.clone() },
+                    playing(&b),
+                    PlayerEvent::EndOfTrack { path: b.clone() },
+                ],
+                "gapless: {gapless}"
+            );
+            assert_eq!(player.current_track(), Some(b.clone()));
+        }
+    }
+
+    #[test]
+    fn seek_moves_position() {
+        let dir = TempDir::new("seek");
+        let a = dir.wav("a.wav", Duration::from_secs(3));
+
+        let player = LocalPlayer::with_output(&AudioOutput::Null).unwrap();
+        let events = player.events();
+        player.start_tracks(vec![a.clone()], 0).unwrap();
+        player.pause();
+        player.seek(Duration::from_secs(2)).unwrap();
+
+        assert!(player.position() >= Duration::from_secs(2));
+        let last = events.try_iter().last();
+        assert!(
+            matches!(last, Some(PlayerEvent::Paused { ref path, position_ms }) if *path == a && position_ms >= 2000),
+            "{last:?}"
+        );
+
+        // the rest of the track is played after the seeked position
+        player.play().unwrap();
+        let start = std::time::Instant::now();
+        assert_eq!(
+            events_until_end(&player, &a).last(),
+            Some(&PlayerEvent::EndOfTrack { path: a })
+        );
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn start_undecodable_track() {
+        let dir = TempDir::new("undecodable");
+        let a = dir.wav("a.wav", Duration::from_secs(3));
+        let b = dir.wav("b.wav", Duration::from_secs(3));
+        let invalid = dir.0.join("invalid.wav");
+        std::fs::write(&invalid, b"not a wav file").unwrap();
+
+        let player = LocalPlayer::with_output(&AudioOutput::Null).unwrap();
+        player.start_tracks(vec![a.clone(), b.clone()], 1).unwrap();
+
+        // the player keeps playing the previous queue
+        assert!(player.start_tracks(vec![invalid.clone()], 0).is_err());
+        assert!(
+            player
+                .start_tracks(vec![dir.0.join("missing.wav")], 0)
+                .is_err()
+        );
+        assert_eq!(player.current_track(), Some(b.clone()));
+        assert_eq!(player.queue(), vec![a, b]);
+        assert!(player.is_playing());
+
+        let player = LocalPlayer::with_output(&AudioOutput::Null).unwrap();
+        assert!(player.start_tracks(vec![invalid], 0).is_err());
+        assert_eq!(player.current_track(), None);
+        assert!(player.queue().is_empty());
+    }
+
+    /// plays two tracks to a WAV output, returning the output's samples
+    /// from the first to the last non-silent sample
+    fn play_to_wav(name: &str, gapless: bool) -> Vec<f32> {
+        let dir = TempDir::new(name);
+        let a = dir.wav("a.wav", Duration::from_millis(300));
+        let b = dir.wav("b.wav", Duration::from_millis(300));
+        let output = dir.0.join("output.wav");
+
+        let player = LocalPlayer::with_output(&AudioOutput::Wav(output.clone())).unwrap();
+        player.set_gapless(gapless);
+        player.start_tracks(vec![a, b.clone()], 0).unwrap();
+        events_until_end(&player, &b);
+        // let the output write the end of the track
+        std::thread::sleep(Duration::from_millis(100));
+        drop(player);
+
+        let content = std::fs::read(&output).unwrap();
+        let samples = content[44..]
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        let first = samples.iter().position(|s| s.abs() > 0.25).unwrap();
+        let last = samples.iter().rposition(|s| s.abs() > 0.25).unwrap();
+        samples[first..=last].to_vec()
+    }
+
+    /// the number of samples of the two tracks played by [`play_to_wav`]
+    const PLAYED_SAMPLES: usize = 2 * 13230 * CHANNELS as usize;
+
+    #[test]
+    fn gapless_playback_without_gap() {
+        let samples = play_to_wav("gapless", true);
+        // both tracks are played back to back, without silent samples between them.
+        // The output may be a few samples longer because of the sink's resampling.
+        assert!(samples.iter().all(|s| s.abs() > 0.25));
+        assert!(samples.len() >= PLAYED_SAMPLES, "{}", samples.len());
+    }
+
+    #[test]
+    fn playback_without_gapless() {
+        let samples = play_to_wav("no_gapless", false);
+        let n_played = samples.iter().filter(|s| s.abs() > 0.25).count();
+        assert!(n_played >= PLAYED_SAMPLES, "{n_played}");
+    }
+}
@@ -0,0 +1,414 @@
+use crate::model::Track;
+use anyhow::{Context, Result};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// A playlist file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistFormat {
+    /// M3U playlist, including its extended and UTF-8 (M3U8) variants
+    M3u,
+    /// PLS playlist
+    Pls,
+}
+
+/// An entry of a playlist file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlaylistEntry {
+    /// the entry's location, which is either a file path or a URI
+    pub location: String,
+    /// the entry's display title, usually in the "artists - title" form
+    pub title: Option<String>,
+    pub duration: Option<Duration>,
+}
+
+/// A playlist loaded from or stored to a playlist file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Playlist {
+    pub name: Option<String>,
+    pub entries: Vec<PlaylistEntry>,
+}
+
+impl PlaylistFormat {
+    /// gets the playlist format of a file based on its extension
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "m3u" | "m3u8" => Some(Self::M3u),
+            "pls" => Some(Self::Pls),
+            _ => None,
+        }
+    }
+}
+
+impl PlaylistEntry {
+    /// creates an entry of a local track
+    #[must_use]
+    pub fn from_track(track: &Track) -> Self {
+        let artists = track.artists_info();
+        Self {
+            location: track.path.display().to_string(),
+            title: Some(if artists.is_empty() {
+                track.name.clone()
+            } else {
+                format!("{artists} - {}", track.name)
+            }),
+            duration: Some(track.duration),
+        }
+    }
+
+    /// whether the entry's location is a URI rather than a file path
+    #[must_use]
+    pub fn is_uri(&self) -> bool {
+        self.location.contains("://") || self.location.starts_with("spotify:")
+    }
+
+    /// splits the entry's display title into the artists and the track's title.
+    ///
+    /// Falls back to the location's file name for an entry without a title.
+    #[must_use]
+    pub fn artists_and_title(&self) -> (Option<&str>, &str) {
+        let Some(title) = &self.title else {
+            let name = self.location.rsplit(['/', '\\']).next().unwrap_or_default();
+            let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+            return (None, stem);
+        };
+        title
+            .split_once(" - ")
+            .map_or((None, title.trim()), |(artists, title)| {
+                (Some(artists.trim()), title.trim())
+            })
+    }
+}
+
+impl Playlist {
+    /// creates a playlist of local tracks
+    #[must_use]
+    pub fn from_tracks(name: Option<String>, tracks: &[Track]) -> Self {
+        Self {
+            name,
+            entries: tracks.iter().map(PlaylistEntry::from_track).collect(),
+        }
+    }
+
+    /// parses a playlist from its content in the given format
+    pub fn parse(content: &str, format: PlaylistFormat) -> Result<Self> {
+        match format {
+            PlaylistFormat::M3u => Ok(parse_m3u(content)),
+            PlaylistFormat::Pls => parse_pls(content),
+        }
+    }
+
+    /// serializes the playlist into the given format.
+    ///
+    /// M3U playlists are written in the extended form, with the entries' metadata stored in `#EXTINF` lines.
+    #[must_use]
+    pub fn serialize(&self, format: PlaylistFormat) -> String {
+        match format {
+            PlaylistFormat::M3u => serialize_m3u(self),
+            PlaylistFormat::Pls => serialize_pls(self),
+        }
+    }
+
+    /// loads a playlist file, detecting its format from the file's extension.
+    ///
+    /// Relative file paths in the playlist are resolved against the playlist file's folder.
+    pub fn load(path: &Path) -> Result<Self> {
+        let format = PlaylistFormat::from_path(path)
+            .with_context(|| format!("unsupported playlist file {}", path.display()))?;
+        let content = std::fs::read(path).with_context(|| format!("read {}", path.display()))?;
+        // legacy M3U files may not be UTF-8 encoded
+        let mut playlist = Self::parse(&String::from_utf8_lossy(&content), format)
+            .with_context(|| format!("parse {}", path.display()))?;
+
+        if let Some(dir) = path.parent() {
+            for entry in playlist.entries.iter_mut().filter(|e| !e.is_uri()) {
+                if Path::new(&entry.location).is_relative() {
+                    entry.location = dir.join(&entry.location).display().to_string();
+                }
+            }
+        }
+        Ok(playlist)
+    }
+
+    /// stores the playlist into a file, using the format matching the file's extension
+    pub fn store(&self, path: &Path) -> Result<()> {
+        let format = PlaylistFormat::from_path(path)
+            .with_context(|| format!("unsupported playlist file {}", path.display()))?;
+        std::fs::write(path, self.serialize(format))
+            .with_context(|| format!("write {}", path.display()))
+    }
+
+    /// gets the file paths of the playlist's entries, skipping entries located by URIs
+    #[must_use]
+    pub fn file_paths(&self) -> Vec<PathBuf> {
+        self.entries
+            .iter()
+            .filter(|e| !e.is_uri())
+            .map(|e| PathBuf::from(&e.location))
+            .collect()
+    }
+}
+
+fn parse_m3u(content: &str) -> Playlist {
+    let mut playlist = Playlist::default();
+    // metadata of the next entry, parsed from an `#EXTINF` line
+    let mut info: Option<(Option<Duration>, Option<String>)> = None;
+
+    for line in content.lines().map(str::trim) {
+        let line = line.trim_start_matches('\u{feff}');
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("#EXTINF:") {
+            // `#EXTINF:<duration> [attributes],<title>`
+            let (duration, title) = value.split_once(',').unwrap_or((value, ""));
+            let duration = duration
+                .split_whitespace()
+                .next()
+                .and_then(|d| d.parse::<f64>().ok())
+                .filter(|d| *d >= 0.0)
+                .map(Duration::from_secs_f64);
+            let title = Some(title.trim().to_string()).filter(|t| !t.is_empty());
+            info = Some((duration, title));
+        } else if let Some(name) = line.strip_prefix("#PLAYLIST:") {
+            playlist.name = Some(name.trim().to_string());
+        } else if !line.starts_with('#') {
+            let (duration, title) = info.take().unwrap_or_default();
+            playlist.entries.push(PlaylistEntry {
+                location: line.to_string(),
+                title,
+                duration,
+            });
+        }
+    }
+    playlist
+}
+
+fn serialize_m3u(playlist: &Playlist) -> String {
+    let mut out = String::from("#EXTM3U\n");
+    if let Some(name) = &playlist.name {
+        writeln!(out, "#PLAYLIST:{name}").unwrap();
+    }
+    for entry in &playlist.entries {
+        if entry.title.is_some() || entry.duration.is_some() {
+            writeln!(
+                out,
+                "#EXTINF:{},{}",
+                entry.duration.map_or(-1, |d| d.as_secs() as i64),
+                entry.title.as_deref().unwrap_or_default()
+            )
+            .unwrap();
+        }
+        writeln!(out, "{}", entry.location).unwrap();
+    }
+    out
+}
+
+fn parse_pls(content: &str) -> Result<Playlist> {
+    let mut lines = content
+        .lines()
+        .map(|l| l.trim().trim_start_matches('\u{feff}'))
+        .filter(|l| !l.is_empty() && !l.starts_with([';', '#']));
+    if !lines
+        .next()
+        .is_some_and(|l| l.eq_ignore_ascii_case("[playlist]"))
+    {
+        anyhow::bail!("missing [playlist] section header");
+    }
+
+    // entries keyed by their numbers
+    let mut entries: BTreeMap<usize, PlaylistEntry> = BTreeMap::new();
+    for line in lines {
+        let Some((key, value)) = line.split_once('=') else {
+            anyhow::bail!("invalid line: {line}");
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim();
+
+        let split = key.find(|c: char| c.is_ascii_digit()).unwrap_or(key.len());
+        let (field, number) = key.split_at(split);
+        let Ok(number) = number.parse::<usize>() else {
+            // global fields such as `NumberOfEntries` and `Version` are not needed
+            continue;
+        };
+        let entry = entries.entry(number).or_default();
+        match field {
+            "file" => entry.location = value.to_string(),
+            "title" => entry.title = Some(value.to_string()).filter(|t| !t.is_empty()),
+            "length" => {
+                entry.duration = value
+                    .parse::<i64>()
+                    .ok()
+                    .and_then(|secs| u64::try_from(secs).ok())
+                    .map(Duration::from_secs);
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(number) = entries
+        .iter()
+        .find_map(|(number, e)| e.location.is_empty().then_some(number))
+    {
+        anyhow::bail!("missing File{number} entry");
+    }
+    Ok(Playlist {
+        name: None,
+        entries: entries.into_values().collect(),
+    })
+}
+
+fn serialize_pls(playlist: &Playlist) -> String {
+    let mut out = String::from("[playlist]\n");
+    for (i, entry) in playlist.entries.iter().enumerate() {
+        let number = i + 1;
+        writeln!(out, "File{number}={}", entry.location).unwrap();
+        if let Some(title) = &entry.title {
+            writeln!(out, "Title{number}={title}").unwrap();
+        }
+        writeln!(
+            out,
+            "Length{number}={}",
+            entry.duration.map_or(-1, |d| d.as_secs() as i64)
+        )
+        .unwrap();
+    }
+    writeln!(out, "NumberOfEntries={}", playlist.entries.len()).unwrap();
+    writeln!(out, "Version=2").unwrap();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playlist() -> Playlist {
+        Playlist {
+            name: Some("Mix".to_string()),
+            entries: vec![
+                PlaylistEntry {
+                    location: "/music/a.flac".to_string(),
+                    title: Some("Artist A - Song A".to_string()),
+                    duration: Some(Duration::from_secs(215)),
+                },
+                PlaylistEntry {
+                    location: "spotify:track:4uLU6hMCjMI75M1A2tKUQC".to_string(),
+                    title: None,
+                    duration: None,
+                },
+                PlaylistEntry {
+                    location: "relative/b.mp3".to_string(),
+                    title: Some("Song B".to_string()),
+                    duration: None,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn m3u_round_trip() {
+        let playlist = playlist();
+        let content = playlist.serialize(PlaylistFormat::M3u);
+        assert!(content.starts_with("#EXTM3U\n#PLAYLIST:Mix\n"));
+        assert_eq!(
+            Playlist::parse(&content, PlaylistFormat::M3u).unwrap(),
+            playlist
+        );
+    }
+
+    #[test]
+    fn pls_round_trip() {
+        // PLS files don't store the playlist's name
+        let playlist = Playlist {
+            name: None,
+            ..playlist()
+        };
+        let content = playlist.serialize(PlaylistFormat::Pls);
+        assert!(content.contains("NumberOfEntries=3\n"));
+        assert_eq!(
+            Playlist::parse(&content, PlaylistFormat::Pls).unwrap(),
+            playlist
+        );
+    }
+
+    #[test]
+    fn m3u_extinf_without_path() {
+        let content = "\u{feff}#EXTM3U\n\
+            #EXTINF:10,Dropped - Info\n\
+            #EXTINF:-1,Artist - Title\n\
+            \n\
+            a.mp3\n\
+            b.mp3\n\
+            #EXTINF:20,Trailing - Info\n";
+        let playlist = Playlist::parse(content, PlaylistFormat::M3u).unwrap();
+        assert_eq!(
+            playlist.entries,
+            vec![
+                PlaylistEntry {
+                    location: "a.mp3".to_string(),
+                    title: Some("Artist - Title".to_string()),
+                    duration: None,
+                },
+                PlaylistEntry {
+                    location: "b.mp3".to_string(),
+                    title: None,
+                    duration: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn pls_malformed() {
+        // the number of entries is taken from the entries, not from `NumberOfEntries`
+        let content = "[playlist]\nFile2=b.mp3\nFile1=a.mp3\nTitle1=A\nNumberOfEntries=5\n";
+        let playlist = Playlist::parse(content, PlaylistFormat::Pls).unwrap();
+        assert_eq!(playlist.file_paths(), ["a.mp3", "b.mp3"].map(PathBuf::from));
+        assert_eq!(playlist.entries[0].title.as_deref(), Some("A"));
+
+        assert!(Playlist::parse("File1=a.mp3\n", PlaylistFormat::Pls).is_err());
+        assert!(Playlist::parse("[playlist]\nTitle1=A\n", PlaylistFormat::Pls).is_err());
+        assert!(Playlist::parse("[playlist]\nFile1\n", PlaylistFormat::Pls).is_err());
+    }
+
+    #[test]
+    fn load_relative_paths() {
+        let dir =
+            std::env::temp_dir().join(format!("local_player_playlist_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("mix.m3u8");
+        playlist().store(&path).unwrap();
+
+        let loaded = Playlist::load(&path);
+        std::fs::remove_dir_all(&dir).unwrap();
+        let loaded = loaded.unwrap();
+        assert_eq!(
+            loaded.file_paths(),
+            vec![PathBuf::from("/music/a.flac"), dir.join("relative/b.mp3")]
+        );
+        assert_eq!(loaded.entries[1].location, playlist().entries[1].location);
+    }
+
+    #[test]
+    fn entry_artists_and_title() {
+        let entry = |location: &str, title: Option<&str>| PlaylistEntry {
+            location: location.to_string(),
+            title: title.map(str::to_string),
+            duration: None,
+        };
+        assert_eq!(
+            entry("a.mp3", Some("Artist - Title")).artists_and_title(),
+            (Some("Artist"), "Title")
+        );
+        assert_eq!(
+            entry("/music/Some Song.flac", None).artists_and_title(),
+            (None, "Some Song")
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use anyhow::{Context, Result};
+use lofty::prelude::*;
+use std::path::Path;
+
+/// Replay gain information of an audio file, read from its `REPLAYGAIN_*` tags
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ReplayGain {
+    /// the track's gain in dB
+    pub track_gain: Option<f32>,
+    /// the track's peak amplitude, where `1.0` is the full scale
+    pub track_peak: Option<f32>,
+    /// the album's gain in dB
+    pub album_gain: Option<f32>,
+    /// the album's peak amplitude, where `1.0` is the full scale
+    pub album_peak: Option<f32>,
+}
+
+/// The replay gain values used to normalize the loudness of tracks
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GainMode {
+    /// normalizes every track to the same loudness
+    #[default]
+    Track,
+    /// normalizes albums to the same loudness, preserving the loudness differences between
+    /// an album's tracks. Tracks without album gain tags fall back to their track gain.
+    Album,
+}
+
+/// Loudness normalization settings of the local player
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Normalization {
+    pub mode: GainMode,
+    /// gain in dB applied to files without replay gain tags
+    pub fallback_gain: f32,
+}
+
+impl ReplayGain {
+    /// reads the replay gain information of an audio file.
+    ///
+    /// Missing or malformed tags are ignored.
+    pub fn read(path: &Path) -> Result<Self> {
+        let file =
+            lofty::read_from_path(path).with_context(|| format!("read {}", path.display()))?;
+        let Some(tag) = file.primary_tag().or_else(|| file.first_tag()) else {
+            return Ok(Self::default());
+        };
+        let value = |key| tag.get_string(key).and_then(parse_value);
+
+        Ok(Self {
+            track_gain: value(ItemKey::ReplayGainTrackGain),
+            track_peak: value(ItemKey::ReplayGainTrackPeak),
+            album_gain: value(ItemKey::ReplayGainAlbumGain),
+            album_peak: value(ItemKey::ReplayGainAlbumPeak),
+        })
+    }
+
+    /// gets the amplitude factor to apply to the file's samples, and whether the factor is known
+    /// to not clip the samples.
+    ///
+    /// The gain is reduced so that the file's peak doesn't exceed the full scale.
+    #[must_use]
+    pub fn factor(&self, normalization: &Normalization) -> (f32, bool) {
+        let (gain, peak) = match normalization.mode {
+            GainMode::Album if self.album_gain.is_some() => (self.album_gain, self.album_peak),
+            _ => (self.track_gain, self.track_peak),
+        };
+        let factor = db_to_factor(gain.unwrap_or(normalization.fallback_gain));
+
+        match peak.filter(|p| *p > 0.0) {
+            Some(peak) => (factor.min(1.0 / peak), true),
+            None => (factor, factor <= 1.0),
+        }
+    }
+}
+
+/// parses a replay gain tag value, e.g. `-6.48 dB` or `0.988312`
+fn parse_value(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let value = value
+        .strip_suffix("dB")
+        .or_else(|| value.strip_suffix("db"))
+        .unwrap_or(value);
+    value.trim().parse::<f32>().ok().filter(|v| v.is_finite())
+}
+
+fn db_to_factor(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
@@ -0,0 +1,331 @@
+use crate::model::{Album, Artist, Track};
+use anyhow::{Context, Result};
+use lofty::{file::TaggedFile, prelude::*, tag::Tag};
+use std::path::{Path, PathBuf};
+
+/// file extensions of the audio formats that can be scanned and played
+const AUDIO_FILE_EXTENSIONS: &[&str] = &["mp3", "flac", "ogg", "oga", "m4a", "mp4", "aac", "wav"];
+
+/// whether a file is an audio file supported by the scanner, based on its extension
+#[must_use]
+pub fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            AUDIO_FILE_EXTENSIONS
+                .iter()
+                .any(|e| e.eq_ignore_ascii_case(ext))
+        })
+}
+
+/// whether a file or directory is hidden, i.e. its name starts with a dot
+#[must_use]
+pub fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
+
+/// lists the audio files inside the given music directories, sorted by path.
+///
+/// Symbolic links are followed. Hidden files and directories are skipped, e.g. `.Trash` folders
+/// or the `._*` metadata files created by macOS, which have audio extensions but cannot be played.
+pub fn list_audio_files(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut paths = dirs
+        .iter()
+        .flat_map(|dir| {
+            walkdir::WalkDir::new(dir)
+                .follow_links(true)
+                .into_iter()
+                .filter_entry(|entry| entry.depth() == 0 || !is_hidden(entry.path()))
+        })
+        .filter_map(|entry| match entry {
+            Ok(entry) => Some(entry),
+            Err(err) => {
+                tracing::warn!("Failed to read a music directory entry: {err:#}");
+                None
+            }
+        })
+        .filter(|entry| entry.file_type().is_file() && is_audio_file(entry.path()))
+        .map(walkdir::DirEntry::into_path)
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// scans the given music directories and reads the tags of every audio file found.
+///
+/// Files whose tags cannot be read are skipped.
+#[must_use]
+pub fn scan_dirs(dirs: &[PathBuf]) -> Vec<Track> {
+    list_audio_files(dirs)
+        .into_iter()
+        .filter_map(|path| match read_track(&path) {
+            Ok(track) => Some(track),
+            Err(err) => {
+                tracing::warn!("Failed to read track {}: {err:#}", path.display());
+                None
+            }
+        })
+        .collect()
+}
+
+/// reads a local track from an audio file.
+///
+/// Supported tag formats are `ID3v2`, Vorbis comments (Ogg and FLAC) and MP4 atoms.
+/// Missing tags fall back to sensible defaults, e.g. the file name for the track's title.
+pub fn read_track(path: &Path) -> Result<Track> {
+    let file = lofty::read_from_path(path).with_context(|| format!("read {}", path.display()))?;
+    let duration = file.properties().duration();
+
+    let Some(tag) = tag(&file) else {
+        return Ok(Track {
+            path: path.to_path_buf(),
+            name: file_stem(path),
+            artists: vec![],
+            album: None,
+            track_number: None,
+            disc_number: None,
+            duration,
+            year: None,
+        });
+    };
+
+    let artists = read_artists(tag, ItemKey::TrackArtists)
+        .or_else(|| read_artists(tag, ItemKey::TrackArtist))
+        .unwrap_or_default();
+    let year = tag.date().map(|date| u32::from(date.year));
+
+    let album = tag.album().map(|name| {
+        let album_artists =
+            read_artists(tag, ItemKey::AlbumArtist).unwrap_or_else(|| artists.clone());
+        Album::new(
+            name.into_owned(),
+            album_artists,
+            year.map(|y| y.to_string()).unwrap_or_default(),
+        )
+    });
+
+    Ok(Track {
+        path: path.to_path_buf(),
+        name: tag
+            .title()
+            .map_or_else(|| file_stem(path), std::borrow::Cow::into_owned),
+        artists,
+        album,
+        track_number: tag.track(),
+        disc_number: tag.disk(),
+        duration,
+        year,
+    })
+}
+
+/// gets the tag of a file with the most information, preferring the file's primary tag type
+fn tag(file: &TaggedFile) -> Option<&Tag> {
+    file.primary_tag().or_else(|| file.first_tag())
+}
+
+/// reads the artists stored in a tag item, splitting values that pack multiple artists.
+///
+/// Returns `None` if the tag doesn't have the item.
+fn read_artists(tag: &Tag, key: ItemKey) -> Option<Vec<Artist>> {
+    let mut artists = Vec::new();
+    for name in tag
+        .get_strings(key)
+        .flat_map(|value| value.split(['\0', ';']))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        if !artists.iter().any(|a: &Artist| a.name == name) {
+            artists.push(Artist::new(name.to_string()));
+        }
+    }
+    (!artists.is_empty()).then_some(artists)
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// a temporary folder removed when dropped
+    pub struct TempDir(pub PathBuf);
+
+    impl TempDir {
+        pub fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("local_player_{name}_{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        /// writes a silent 8kHz mono WAV file with RIFF INFO tags, e.g. `(*b"INAM", "title")`
+        pub fn wav(&self, name: &str, duration: Duration, tags: &[([u8; 4], &str)]) -> PathBuf {
+            let samples = vec![0; (duration.as_millis() * 16) as usize];
+            let mut info = b"INFO".to_vec();
+            for (id, value) in tags {
+                let mut value = value.as_bytes().to_vec();
+                value.push(0);
+                info.extend(id);
+                info.extend(u32::try_from(value.len()).unwrap().to_le_bytes());
+                if value.len() % 2 == 1 {
+                    value.push(0);
+                }
+                info.extend(value);
+            }
+
+            let mut chunks = chunk(
+                *b"fmt ",
+                &[
+                    1, 0, // PCM
+                    1, 0, // mono
+                    0x40, 0x1f, 0, 0, // 8000Hz
+                    0x80, 0x3e, 0, 0, // 16000 bytes per second
+                    2, 0, // block align
+                    16, 0, // bits per sample
+                ],
+            );
+            chunks.extend(chunk(*b"data", &samples));
+            if !tags.is_empty() {
+                chunks.extend(chunk(*b"LIST", &info));
+            }
+            let mut data = b"WAVE".to_vec();
+            data.extend(chunks);
+
+            let path = self.0.join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, chunk(*b"RIFF", &data)).unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            std::fs::remove_dir_all(&self.0).unwrap_or_default();
+        }
+    }
+
+    fn chunk(id: [u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut chunk = id.to_vec();
+        chunk.extend(u32::try_from(data.len()).unwrap().to_le_bytes());
+        chunk.extend(data);
+        chunk
+    }
+
+    #[test]
+    fn audio_file_extensions() {
+        for name in ["a.mp3", "a.FLAC", "a.Ogg", "a.m4a", "a.wav", "dir/a.b.mp3"] {
+            assert!(is_audio_file(Path::new(name)), "{name}");
+        }
+        for name in ["a.txt", "a.lrc", "a.m3u", "mp3", "a", "a.mp3.part"] {
+            assert!(!is_audio_file(Path::new(name)), "{name}");
+        }
+    }
+
+    #[test]
+    fn list_audio_files_in_dirs() {
+        let dir = TempDir::new("list");
+        let music = dir.0.join("music");
+        for name in [
+            "b.mp3",
+            "a.flac",
+            "album/c.ogg",
+            "cover.jpg",
+            "album/lyrics.lrc",
+            "._b.mp3",
+            ".trash/d.mp3",
+        ] {
+            let path = music.join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"").unwrap();
+        }
+        let other = dir.0.join(".other");
+        std::fs::create_dir_all(&other).unwrap();
+        std::fs::write(other.join("e.wav"), b"").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&other, music.join("linked")).unwrap();
+
+        let mut expected = vec![
+            music.join("a.flac"),
+            music.join("album/c.ogg"),
+            music.join("b.mp3"),
+        ];
+        // symbolic links are followed
+        #[cfg(unix)]
+        expected.push(music.join("linked/e.wav"));
+        assert_eq!(list_audio_files(std::slice::from_ref(&music)), expected);
+
+        // a hidden music directory is scanned, and listing a directory twice doesn't duplicate files
+        assert_eq!(
+            list_audio_files(&[other.clone(), other.clone()]),
+            vec![other.join("e.wav")]
+        );
+        assert!(list_audio_files(&[dir.0.join("missing")]).is_empty());
+    }
+
+    #[test]
+    fn read_tagged_track() {
+        let dir = TempDir::new("tagged");
+        let path = dir.wav(
+            "song.wav",
+            Duration::from_secs(2),
+            &[
+                (*b"INAM", "Title"),
+                (*b"IART", "Artist A; Artist B"),
+                (*b"IPRD", "Album"),
+                (*b"ICRD", "2020"),
+            ],
+        );
+
+        let track = read_track(&path).unwrap();
+        assert_eq!(track.path, path);
+        assert_eq!(track.name, "Title");
+        assert_eq!(
+            track.artists,
+            vec![
+                Artist::new("Artist A".to_string()),
+                Artist::new("Artist B".to_string())
+            ]
+        );
+        let album = track.album.unwrap();
+        assert_eq!(album.name, "Album");
+        // the album's artists default to the track's artists
+        assert_eq!(album.artists, track.artists);
+        assert_eq!(track.year, Some(2020));
+        assert_eq!(track.duration, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn read_track_without_tags() {
+        let dir = TempDir::new("untagged");
+        let path = dir.wav("01 - Song.wav", Duration::from_secs(1), &[]);
+
+        let track = read_track(&path).unwrap();
+        assert_eq!(track.name, "01 - Song");
+        assert!(track.artists.is_empty());
+        assert_eq!(track.album, None);
+        assert_eq!(track.year, None);
+        assert_eq!(track.duration, Duration::from_secs(1));
+
+        // a tag without a title falls back to the file name too
+        let path = dir.wav("Other.wav", Duration::from_secs(1), &[(*b"IART", "Artist")]);
+        let track = read_track(&path).unwrap();
+        assert_eq!(track.name, "Other");
+        assert_eq!(track.artists, vec![Artist::new("Artist".to_string())]);
+
+        // files that are not audio files fail to be read, and are skipped by a scan
+        std::fs::write(dir.0.join("invalid.mp3"), b"not an audio file").unwrap();
+        assert!(read_track(&dir.0.join("invalid.mp3")).is_err());
+        let names = scan_dirs(std::slice::from_ref(&dir.0))
+            .into_iter()
+            .map(|t| t.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["01 - Song", "Other"]);
+    }
+}
@@ -0,0 +1,210 @@
+use parking_lot::Mutex;
+use rodio::{
+    ChannelCount, SampleRate, Source,
+    source::{SeekError, UniformSourceIterator},
+};
+use std::{sync::Arc, time::Duration};
+
+pub(crate) type BoxedSource = Box<dyn Source + Send>;
+
+/// A slot to pass a track's source to the source of the track before it
+#[derive(Default)]
+pub(crate) struct Slot {
+    pub source: Option<BoxedSource>,
+    /// the duration of the source that has been played as part of a crossfade
+    pub played: Duration,
+}
+
+pub(crate) type SharedSlot = Arc<Mutex<Slot>>;
+
+/// A track's source that crossfades into the source of the next track during its last seconds.
+///
+/// The next track's source is taken from the `next` slot when the crossfade starts, then put back
+/// into the slot when the track ends, so that the rest of it is played by a [`SlotSource`].
+pub(crate) struct CrossfadeSource {
+    inner: BoxedSource,
+    next: SharedSlot,
+    /// the next track's source being mixed into the track's last samples
+    mixing: Option<UniformSourceIterator<BoxedSource>>,
+    /// the number of samples in the crossfade
+    fade_len: u64,
+    /// the number of samples of the crossfade played so far
+    faded: u64,
+    /// the number of samples left in the track, if the track's duration is known
+    remaining: Option<u64>,
+}
+
+impl CrossfadeSource {
+    pub fn new(inner: BoxedSource, next: SharedSlot, crossfade: Duration) -> Self {
+        let remaining = inner
+            .total_duration()
+            .map(|d| samples(d, inner.channels(), inner.sample_rate()));
+        let fade_len = samples(crossfade, inner.channels(), inner.sample_rate());
+        Self {
+            inner,
+            next,
+            mixing: None,
+            fade_len,
+            faded: 0,
+            remaining,
+        }
+    }
+
+    /// starts mixing the next track's source if the track reaches its crossfade
+    fn start_crossfade(&mut self) {
+        if self.fade_len == 0 || self.mixing.is_some() {
+            return;
+        }
+        let Some(remaining) = self.remaining.filter(|r| *r <= self.fade_len) else {
+            return;
+        };
+        let Some(next) = self.next.lock().source.take() else {
+            return;
+        };
+        self.mixing = Some(UniformSourceIterator::new(
+            next,
+            self.inner.channels(),
+            self.inner.sample_rate(),
+        ));
+        self.fade_len = remaining.max(1);
+        self.faded = 0;
+    }
+
+    /// puts the next track's source back into the slot
+    fn stop_crossfade(&mut self, played: Duration) {
+        if let Some(next) = self.mixing.take() {
+            let mut slot = self.next.lock();
+            slot.source = Some(Box::new(next));
+            slot.played = played;
+        }
+    }
+}
+
+impl Iterator for CrossfadeSource {
+    type Item = rodio::Sample;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(sample) = self.inner.next() else {
+            let played = samples_duration(self.faded, self.channels(), self.sample_rate());
+            self.stop_crossfade(played);
+            return None;
+        };
+        if let Some(remaining) = &mut self.remaining {
+            *remaining = remaining.saturating_sub(1);
+        }
+
+        self.start_crossfade();
+        let Some(next) = &mut self.mixing else {
+            return Some(sample);
+        };
+        let progress = (self.faded as f32 / self.fade_len as f32).min(1.0);
+        self.faded += 1;
+        let next_sample = next.next().unwrap_or_default();
+        Some(sample * (1.0 - progress) + next_sample * progress)
+    }
+}
+
+impl Source for CrossfadeSource {
+    fn current_span_len(&self) -> Option<usize> {
+        self.inner.current_span_len()
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.inner.try_seek(pos)?;
+        // restart the next track's source if seeking away from an ongoing crossfade
+        if let Some(next) = &mut self.mixing {
+            next.try_seek(Duration::ZERO).unwrap_or_default();
+        }
+        self.stop_crossfade(Duration::ZERO);
+        self.remaining = self
+            .inner
+            .total_duration()
+            .map(|d| samples(d.saturating_sub(pos), self.channels(), self.sample_rate()));
+        Ok(())
+    }
+}
+
+/// A source that plays the source passed through a slot.
+///
+/// The slot's source is taken when the source starts playing. Until then, the source's
+/// properties are read from the slot's source.
+pub(crate) struct SlotSource {
+    slot: SharedSlot,
+    source: Option<BoxedSource>,
+}
+
+impl SlotSource {
+    pub fn new(slot: SharedSlot) -> Self {
+        Self { slot, source: None }
+    }
+
+    fn source(&mut self) -> Option<&mut BoxedSource> {
+        if self.source.is_none() {
+            self.source = self.slot.lock().source.take();
+        }
+        self.source.as_mut()
+    }
+
+    fn property<T>(&self, f: impl Fn(&BoxedSource) -> T) -> Option<T> {
+        match &self.source {
+            Some(source) => Some(f(source)),
+            None => self.slot.lock().source.as_ref().map(f),
+        }
+    }
+}
+
+impl Iterator for SlotSource {
+    type Item = rodio::Sample;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.source()?.next()
+    }
+}
+
+impl Source for SlotSource {
+    fn current_span_len(&self) -> Option<usize> {
+        self.property(Source::current_span_len).unwrap_or(Some(0))
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.property(Source::channels).unwrap_or(1)
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.property(Source::sample_rate).unwrap_or(44100)
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.property(Source::total_duration).flatten()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.slot.lock().played = Duration::ZERO;
+        match self.source() {
+            Some(source) => source.try_seek(pos),
+            None => Ok(()),
+        }
+    }
+}
+
+/// gets the number of samples in a duration of audio
+fn samples(duration: Duration, channels: ChannelCount, sample_rate: SampleRate) -> u64 {
+    (duration.as_secs_f64() * f64::from(sample_rate) * f64::from(channels)) as u64
+}
+
+/// gets the duration of a number of samples
+fn samples_duration(samples: u64, channels: ChannelCount, sample_rate: SampleRate) -> Duration {
+    Duration::from_secs_f64(samples as f64 / (f64::from(sample_rate) * f64::from(channels)))
+}
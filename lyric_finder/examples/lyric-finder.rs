@@ -18,8 +18,10 @@ async fn main() -> anyhow::Result<()> {
             track,
             artists,
             lyric,
+            source,
+            ..
         } => {
-            println!("{track} by {artists}'s lyric:\n{lyric}");
+            println!("{track} by {artists}'s lyric (from {source}):\n{lyric}");
         }
         lyric_finder::LyricResult::None => {
             println!("lyric not found!");
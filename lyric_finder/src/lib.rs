@@ -4,6 +4,14 @@
 //!
 //! It ultilizes the [Genius](https://genius.com) website and its APIs to get lyric data.
 //!
+//! Other lyric sources can be used through the [`LyricProvider`] trait, which is implemented by
+//! [`Client`] and by [`LrcLibClient`] for [LRCLIB](https://lrclib.net).
+//! A [`ProviderChain`] tries multiple providers in order.
+//! Search results are scored against the searched song's title, artists and duration:
+//! if no result matches well enough, [`LyricResult::None`] is returned rather than the lyric of another song.
+//!
+//! Synced lyrics can be parsed and written in the LRC format with [`lrc::Lrc`].
+//!
 //! ## Example
 //!
 //! ```rust
@@ -17,6 +25,7 @@
 //!         track,
 //!         artists,
 //!         lyric,
+//!         ..
 //!     } => {
 //!         println!("{} by {}'s lyric:\n{}", track, artists, lyric);
 //!     }
@@ -28,10 +37,20 @@
 //! # }
 //! ```
 
-const SEARCH_BASE_URL: &str = "https://genius.com/api/search";
+pub mod lrc;
+mod lrclib;
+pub mod matching;
+mod provider;
+
+pub use lrclib::LrcLibClient;
+pub use provider::{LyricProvider, ProviderChain, Query};
+
+const GENIUS_BASE_URL: &str = "https://genius.com";
 
+/// A client retrieving lyrics from [Genius](https://genius.com)
 pub struct Client {
     http: reqwest::Client,
+    base_url: String,
 }
 
 #[derive(Debug)]
@@ -40,6 +59,10 @@ pub enum LyricResult {
         track: String,
         artists: String,
         lyric: String,
+        /// whether the lyric is synced, in which case it is in the LRC format
+        synced: bool,
+        /// the name of the provider of the lyric
+        source: String,
     },
     None,
 }
@@ -47,15 +70,22 @@ pub enum LyricResult {
 impl Client {
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            http: reqwest::Client::new(),
-        }
+        Self::from_http_client(&reqwest::Client::new())
     }
 
     /// Construct a client reusing an existing http client
     #[must_use]
     pub fn from_http_client(http: &reqwest::Client) -> Self {
-        Self { http: http.clone() }
+        Self::with_base_url(http, GENIUS_BASE_URL)
+    }
+
+    /// Construct a client sending requests to a Genius-compatible server at `base_url`
+    #[must_use]
+    pub fn with_base_url(http: &reqwest::Client, base_url: impl Into<String>) -> Self {
+        Self {
+            http: http.clone(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
     }
 
     /// Search songs satisfying a given `query`.
@@ -66,7 +96,7 @@ impl Client {
 
         let body = self
             .http
-            .get(format!("{SEARCH_BASE_URL}?q={query}"))
+            .get(format!("{}/api/search?q={query}", self.base_url))
             .send()
             .await?
             .json::<search::Body>()
@@ -113,19 +143,37 @@ impl Client {
     }
 
     /// Get the lyric of a song satisfying a given `query`.
+    ///
+    /// Returns [`LyricResult::None`] if no search result matches the query well enough.
     pub async fn get_lyric(&self, query: &str) -> anyhow::Result<LyricResult> {
+        self.get_best_lyric(query, |result| {
+            matching::text_score(query, &result.title, &result.artist_names)
+        })
+        .await
+    }
+
+    /// Get the lyric of the search result of `query` with the highest `score`.
+    async fn get_best_lyric<F>(&self, query: &str, score: F) -> anyhow::Result<LyricResult>
+    where
+        F: Fn(&search::Result) -> f64,
+    {
         // Perform the search for songs
         let results = self.search_songs(query).await?;
 
-        // Filter to find the first result where the artist names do not contain 'Genius'
+        // Find the best matching result, ignoring the results where the artist names contain 'Genius'
+        // (e.g. Genius translations)
         let result = results
             .into_iter()
-            .find(|result| !result.artist_names.contains("Genius"));
+            .filter(|result| !result.artist_names.contains("Genius"))
+            .map(|result| (score(&result), result))
+            .filter(|(score, _)| *score >= matching::MIN_SCORE)
+            .max_by(|(a, _), (b, _)| a.total_cmp(b));
 
         // If no valid result is found, return LyricResult::None
-        let Some(result) = result else {
+        let Some((score, result)) = result else {
             return Ok(LyricResult::None);
         };
+        log::debug!("found matching song: result={result:?}, score={score}");
 
         // Retrieve the song lyrics from the URL of the result
         let lyric = self.retrieve_lyric(&result.url).await?;
@@ -135,7 +183,24 @@ impl Client {
             track: result.title,
             artists: result.artist_names,
             lyric: Self::process_lyric(&lyric),
+            synced: false,
+            source: "Genius".to_string(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl LyricProvider for Client {
+    fn name(&self) -> &'static str {
+        "Genius"
+    }
+
+    async fn find_lyric(&self, query: &Query) -> anyhow::Result<LyricResult> {
+        // Genius search results don't have the songs' durations
+        self.get_best_lyric(&query.text(), |result| {
+            matching::score(query, &result.title, &result.artist_names, None)
         })
+        .await
     }
 }
 
@@ -249,16 +314,12 @@ mod parse {
         }
 
         match &node.data {
-            NodeData::Text { contents } => {
-                if should_parse {
-                    s.push_str(&contents.borrow().to_string());
-                }
+            NodeData::Text { contents } if should_parse => {
+                s.push_str(&contents.borrow().to_string());
             }
-            NodeData::Element { ref name, .. } => {
+            NodeData::Element { ref name, .. } if should_parse => {
                 if let expanded_name!(html "br") = name.expanded() {
-                    if should_parse {
-                        s.push('\n');
-                    }
+                    s.push('\n');
                 }
             }
             _ => {}
@@ -306,3 +367,188 @@ mod search {
         pub artist_names: String,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// A response of the stand-in server: status code, content type and body
+    type Response = (u16, &'static str, String);
+
+    /// starts a local HTTP stand-in server answering requests with `route`,
+    /// which gets the request's path and the server's base URL. Returns the server's base URL.
+    ///
+    /// Note that a panic in `route` only closes the connection, it doesn't fail the test.
+    async fn serve(route: fn(&str, &str) -> Response) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        tokio::spawn({
+            let base_url = base_url.clone();
+            async move {
+                while let Ok((mut stream, _)) = listener.accept().await {
+                    let mut request = Vec::new();
+                    let mut buf = [0; 1024];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match stream.read(&mut buf).await {
+                            Ok(0) | Err(_) => break,
+                            Ok(n) => request.extend_from_slice(&buf[..n]),
+                        }
+                    }
+                    let request = String::from_utf8_lossy(&request);
+                    let path = request.split(' ').nth(1).unwrap_or_default();
+
+                    let (status, content_type, body) = route(path, &base_url);
+                    let response = format!(
+                        "HTTP/1.1 {status} Status\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    stream.write_all(response.as_bytes()).await.unwrap();
+                }
+            }
+        });
+        base_url
+    }
+
+    fn query() -> Query {
+        Query {
+            track: "Shape of You".to_string(),
+            artists: vec!["Ed Sheeran".to_string()],
+            duration: Some(std::time::Duration::from_secs(234)),
+        }
+    }
+
+    /// routes of a Genius stand-in server finding the lyric of the queried song
+    fn genius_route(path: &str, base_url: &str) -> Response {
+        if path.starts_with("/api/search") {
+            let body = format!(
+                r#"{{"meta":{{"status":200}},"response":{{"hits":[
+                    {{"type":"song","result":{{"url":"{base_url}/ed-sheeran-shape-of-you-lyrics","title":"Shape of You","artist_names":"Ed Sheeran"}}}}
+                ]}}}}"#
+            );
+            (200, "application/json", body)
+        } else if path == "/ed-sheeran-shape-of-you-lyrics" {
+            let body = r#"<html><body><div data-lyrics-container="true">[Verse 1]<br>The club isn't the best place<br>to find a lover</div></body></html>"#;
+            (200, "text/html", body.to_string())
+        } else {
+            (404, "text/plain", String::new())
+        }
+    }
+
+    fn chain(lrclib_url: &str, genius_url: &str) -> ProviderChain {
+        let http = reqwest::Client::new();
+        ProviderChain::new(vec![
+            Box::new(LrcLibClient::with_base_url(&http, lrclib_url)),
+            Box::new(Client::with_base_url(&http, genius_url)),
+        ])
+    }
+
+    #[tokio::test]
+    async fn chain_falls_through_to_genius() {
+        let genius_url = serve(genius_route).await;
+        // LRCLIB finds no lyric, then fails
+        let lrclib_urls = [
+            serve(|_, _| (200, "application/json", "[]".to_string())).await,
+            serve(|_, _| (500, "text/plain", String::new())).await,
+        ];
+
+        for lrclib_url in lrclib_urls {
+            let result = chain(&lrclib_url, &genius_url)
+                .find_lyric(&query())
+                .await
+                .unwrap();
+            let LyricResult::Some {
+                track,
+                lyric,
+                synced,
+                source,
+                ..
+            } = result
+            else {
+                panic!("lyric not found");
+            };
+            assert_eq!(track, "Shape of You");
+            assert_eq!(
+                lyric,
+                "[Verse 1]\nThe club isn't the best place\nto find a lover"
+            );
+            assert!(!synced);
+            assert_eq!(source, "Genius");
+        }
+    }
+
+    #[tokio::test]
+    async fn lrclib_returns_synced_lyric() {
+        let lrclib_url = serve(|path, _| {
+            // a failed request would fall through to Genius
+            if !path.starts_with("/api/search?track_name=Shape+of+You&artist_name=Ed+Sheeran") {
+                return (404, "text/plain", String::new());
+            }
+            let body = r#"[
+                {"trackName":"Shape of You","artistName":"Ed Sheeran","albumName":"÷","duration":234.0,
+                 "plainLyrics":"The club isn't the best place","syncedLyrics":null},
+                {"trackName":"Shape of You","artistName":"Ed Sheeran","albumName":"÷","duration":233.7,
+                 "plainLyrics":"The club isn't the best place","syncedLyrics":"[00:09.37] The club isn't the best place\n"},
+                {"trackName":"Shape of You","artistName":"Someone Else","duration":120.0,
+                 "plainLyrics":null,"syncedLyrics":"[00:01.00] Wrong song"}
+            ]"#;
+            (200, "application/json", body.to_string())
+        })
+        .await;
+        // Genius is only queried if LRCLIB doesn't find the lyric
+        let genius_url = serve(genius_route).await;
+
+        let result = chain(&lrclib_url, &genius_url)
+            .find_lyric(&query())
+            .await
+            .unwrap();
+        let LyricResult::Some {
+            lyric,
+            synced,
+            source,
+            ..
+        } = result
+        else {
+            panic!("lyric not found");
+        };
+        assert_eq!(lyric, "[00:09.37] The club isn't the best place");
+        assert!(synced);
+        assert_eq!(source, "LRCLIB");
+    }
+
+    #[tokio::test]
+    async fn chain_fails_if_a_provider_fails() {
+        // LRCLIB fails and Genius finds no lyric
+        let lrclib_url = serve(|_, _| (500, "text/plain", String::new())).await;
+        let genius_url = serve(|_, _| {
+            let body = r#"{"meta":{"status":200},"response":{"hits":[]}}"#;
+            (200, "application/json", body.to_string())
+        })
+        .await;
+
+        let result = chain(&lrclib_url, &genius_url).find_lyric(&query()).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn mismatched_results_are_rejected() {
+        let lrclib_url = serve(|_, _| {
+            let body = r#"[{"trackName":"Perfect","artistName":"Ed Sheeran","duration":263.0,
+                "plainLyrics":"I found a love","syncedLyrics":null}]"#;
+            (200, "application/json", body.to_string())
+        })
+        .await;
+        let genius_url = serve(|_, _| {
+            let body = r#"{"meta":{"status":200},"response":{"hits":[]}}"#;
+            (200, "application/json", body.to_string())
+        })
+        .await;
+
+        let result = chain(&lrclib_url, &genius_url)
+            .find_lyric(&query())
+            .await
+            .unwrap();
+        assert!(matches!(result, LyricResult::None));
+    }
+}
@@ -0,0 +1,447 @@
+//! Parsing and writing of lyrics in the [LRC](https://en.wikipedia.org/wiki/LRC_(file_format)) format.
+//!
+//! Supported features are:
+//! - line timestamps (`[mm:ss.xx]text`), including multiple timestamps per line
+//! - ID tags (e.g. `[ar:Artist]`), with the `[offset:]` tag applied to the timestamps when parsing
+//! - enhanced (word-level) timestamps (`[mm:ss.xx]<mm:ss.xx>word <mm:ss.xx>word`)
+
+use std::{fmt::Write, time::Duration};
+
+/// the keys of the ID tags defined by the LRC format
+const ID_TAG_KEYS: [&str; 11] = [
+    "ar", "al", "ti", "au", "lr", "length", "by", "offset", "re", "tool", "ve",
+];
+
+/// Lyrics in the LRC format
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Lrc {
+    /// ID tags such as `ar` (artist) or `ti` (title), excluding the `offset` tag
+    pub tags: Vec<(String, String)>,
+    /// lines sorted by their timestamps
+    pub lines: Vec<LrcLine>,
+    /// whether the lines are timestamped.
+    /// Unsynced lyrics have their lines timestamped at zero and are written without timestamps.
+    pub synced: bool,
+}
+
+/// A timestamped line of lyrics
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LrcLine {
+    pub time: Duration,
+    pub text: String,
+    /// word-level timestamps of the line, if any
+    pub words: Vec<LrcWord>,
+}
+
+/// A timestamped word (or syllable) of a line
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LrcWord {
+    pub time: Duration,
+    pub text: String,
+}
+
+impl Lrc {
+    /// Parse lyrics in the LRC format.
+    ///
+    /// Parsing is lenient: malformed tags are treated as text. Content without any timestamp
+    /// is parsed as unsynced lyrics.
+    #[must_use]
+    pub fn parse(content: &str) -> Self {
+        let mut tags = vec![];
+        let mut lines = vec![];
+        let mut plain_lines = vec![];
+        let mut offset_ms = 0i64;
+
+        for line in content.lines() {
+            let line = line.trim().trim_start_matches('\u{feff}');
+
+            // a line can have multiple timestamps, e.g. `[00:12.00][01:30.50]text`
+            let mut rest = line;
+            let mut times = vec![];
+            while let Some((tag, text)) = rest.strip_prefix('[').and_then(|s| s.split_once(']')) {
+                let Some(time) = parse_timestamp(tag) else {
+                    break;
+                };
+                times.push(time);
+                rest = text;
+            }
+
+            if times.is_empty() {
+                if let Some((key, value)) = parse_id_tag(line) {
+                    if key == "offset" {
+                        offset_ms = value.trim_start_matches('+').parse().unwrap_or_default();
+                    } else {
+                        tags.push((key, value));
+                    }
+                } else {
+                    plain_lines.push(line.to_string());
+                }
+                continue;
+            }
+
+            let (text, words) = parse_words(rest);
+            lines.extend(times.into_iter().map(|time| LrcLine {
+                time,
+                text: text.clone(),
+                words: words.clone(),
+            }));
+        }
+
+        if lines.is_empty() {
+            // keep the empty lines separating the sections of unsynced lyrics, except at the edges
+            let start = plain_lines.iter().position(|l| !l.is_empty());
+            let end = plain_lines.iter().rposition(|l| !l.is_empty());
+            let plain_lines = match (start, end) {
+                (Some(start), Some(end)) => plain_lines.drain(start..=end).collect(),
+                _ => vec![],
+            };
+            return Self {
+                tags,
+                lines: plain_lines
+                    .into_iter()
+                    .map(|text| LrcLine {
+                        time: Duration::ZERO,
+                        text,
+                        words: vec![],
+                    })
+                    .collect(),
+                synced: false,
+            };
+        }
+
+        // a positive offset makes the lyrics appear sooner
+        let apply_offset = |time: Duration| {
+            let offset = Duration::from_millis(offset_ms.unsigned_abs());
+            if offset_ms >= 0 {
+                time.saturating_sub(offset)
+            } else {
+                time + offset
+            }
+        };
+        for line in &mut lines {
+            line.time = apply_offset(line.time);
+            for word in &mut line.words {
+                word.time = apply_offset(word.time);
+            }
+        }
+        lines.sort_by_key(|l| l.time);
+
+        Self {
+            tags,
+            lines,
+            synced: true,
+        }
+    }
+
+    /// Write the lyrics in the LRC format
+    #[must_use]
+    pub fn serialize(&self) -> String {
+        let mut out = String::new();
+        for (key, value) in &self.tags {
+            writeln!(out, "[{key}:{value}]").unwrap();
+        }
+        for line in &self.lines {
+            if !self.synced {
+                writeln!(out, "{}", line.text).unwrap();
+                continue;
+            }
+
+            write!(out, "[{}]", format_timestamp(line.time)).unwrap();
+            if line.words.is_empty() {
+                out.push_str(&line.text);
+            } else {
+                // the text before the first word timestamp is only kept in the line's text
+                let words_text = line
+                    .words
+                    .iter()
+                    .map(|w| w.text.as_str())
+                    .collect::<String>();
+                out.push_str(
+                    line.text
+                        .strip_suffix(words_text.trim_end())
+                        .unwrap_or_default(),
+                );
+                for word in &line.words {
+                    write!(out, "<{}>{}", format_timestamp(word.time), word.text).unwrap();
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Parse a timestamp in the `mm:ss`, `mm:ss.xx` or `mm:ss.xxx` form
+fn parse_timestamp(s: &str) -> Option<Duration> {
+    let (minutes, seconds) = s.trim().split_once(':')?;
+    if !minutes.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let minutes = minutes.parse::<u64>().ok()?;
+    // some files use a colon as the fraction separator, e.g. `mm:ss:xx`
+    let (seconds, fraction) = seconds
+        .split_once(['.', ':'])
+        .map_or((seconds, ""), |(s, f)| (s, f));
+    if seconds.is_empty() || !seconds.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    if !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let seconds = seconds.parse::<u64>().ok()?;
+    // the fraction is either in centiseconds or milliseconds
+    let millis = match fraction.len() {
+        0 => 0,
+        1 => fraction.parse::<u64>().ok()? * 100,
+        2 => fraction.parse::<u64>().ok()? * 10,
+        _ => fraction.get(..3)?.parse::<u64>().ok()?,
+    };
+    Some(Duration::from_millis(
+        (minutes * 60 + seconds) * 1000 + millis,
+    ))
+}
+
+/// Format a timestamp in the `mm:ss.xx` form, or in the `mm:ss.xxx` form if it has a millisecond precision
+fn format_timestamp(time: Duration) -> String {
+    let millis = time.as_millis();
+    let (minutes, seconds) = (millis / 60_000, (millis / 1000) % 60);
+    if millis.is_multiple_of(10) {
+        format!("{minutes:02}:{seconds:02}.{:02}", (millis % 1000) / 10)
+    } else {
+        format!("{minutes:02}:{seconds:02}.{:03}", millis % 1000)
+    }
+}
+
+/// Parse an ID tag line, e.g. `[ar:Artist]`, returning the tag's key and value.
+///
+/// Unknown tags are not parsed, as they are often section headers of unsynced lyrics, e.g. `[Chorus: Artist]`.
+fn parse_id_tag(line: &str) -> Option<(String, String)> {
+    let (key, value) = line.strip_prefix('[')?.strip_suffix(']')?.split_once(':')?;
+    let key = key.trim().to_ascii_lowercase();
+    if !ID_TAG_KEYS.contains(&key.as_str()) {
+        return None;
+    }
+    Some((key, value.trim().to_string()))
+}
+
+/// Parse a line's text with optional word-level timestamps, returning the line's plain text and its words
+fn parse_words(s: &str) -> (String, Vec<LrcWord>) {
+    let mut words: Vec<LrcWord> = vec![];
+    // the text before the first word timestamp
+    let mut prefix = String::new();
+    let mut rest = s;
+
+    while let Some(start) = rest.find('<') {
+        let time = rest[start + 1..]
+            .split_once('>')
+            .and_then(|(tag, text)| Some((parse_timestamp(tag)?, text)));
+        let Some((time, text)) = time else {
+            // not a timestamp, keep the `<` as text
+            let (text, next) = rest.split_at(start + 1);
+            match words.last_mut() {
+                Some(word) => word.text.push_str(text),
+                None => prefix.push_str(text),
+            }
+            rest = next;
+            continue;
+        };
+
+        let text_before = &rest[..start];
+        match words.last_mut() {
+            Some(word) => word.text.push_str(text_before),
+            None => prefix.push_str(text_before),
+        }
+        words.push(LrcWord {
+            time,
+            text: String::new(),
+        });
+        rest = text;
+    }
+    match words.last_mut() {
+        Some(word) => word.text.push_str(rest),
+        None => prefix.push_str(rest),
+    }
+
+    if words.is_empty() {
+        return (prefix.trim().to_string(), words);
+    }
+    let text = prefix + &words.iter().map(|w| w.text.as_str()).collect::<String>();
+    (text.trim().to_string(), words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn line(time: Duration, text: &str) -> LrcLine {
+        LrcLine {
+            time,
+            text: text.to_string(),
+            words: vec![],
+        }
+    }
+
+    #[test]
+    fn parse_timestamps() {
+        assert_eq!(parse_timestamp("01:02"), Some(ms(62_000)));
+        assert_eq!(parse_timestamp("01:02.5"), Some(ms(62_500)));
+        assert_eq!(parse_timestamp("01:02.34"), Some(ms(62_340)));
+        assert_eq!(parse_timestamp("01:02.345"), Some(ms(62_345)));
+        assert_eq!(parse_timestamp("01:02:34"), Some(ms(62_340)));
+        assert_eq!(parse_timestamp("ar:Artist"), None);
+        assert_eq!(parse_timestamp("01:"), None);
+        assert_eq!(parse_timestamp("01:02.x"), None);
+    }
+
+    #[test]
+    fn parse_multiple_timestamps() {
+        let lrc = Lrc::parse("[00:12.00][01:30.50]Chorus\n[00:05.00]Verse\n");
+        assert!(lrc.synced);
+        assert_eq!(
+            lrc.lines,
+            vec![
+                line(ms(5_000), "Verse"),
+                line(ms(12_000), "Chorus"),
+                line(ms(90_500), "Chorus"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_id_tags_and_offset() {
+        let content =
+            "\u{feff}[ar: Artist ]\n[ti:Title]\n[offset:+500]\n[00:01.00]First\n[00:10.00]Second\n";
+        let lrc = Lrc::parse(content);
+        assert_eq!(
+            lrc.tags,
+            vec![
+                ("ar".to_string(), "Artist".to_string()),
+                ("ti".to_string(), "Title".to_string()),
+            ]
+        );
+        // a positive offset makes the lines appear sooner, without going below zero
+        assert_eq!(
+            lrc.lines,
+            vec![line(ms(500), "First"), line(ms(9_500), "Second")]
+        );
+
+        let lrc = Lrc::parse("[offset:-250]\n[00:01.00]First\n");
+        assert_eq!(lrc.lines, vec![line(ms(1_250), "First")]);
+
+        let lrc = Lrc::parse("[offset:2000]\n[00:01.00]First\n");
+        assert_eq!(lrc.lines, vec![line(Duration::ZERO, "First")]);
+    }
+
+    #[test]
+    fn parse_enhanced_words() {
+        let lrc =
+            Lrc::parse("[offset:100]\n[00:01.00]<00:01.00>Hello <00:01.50>wor<00:01.80>ld a<b\n");
+        let [line] = lrc.lines.as_slice() else {
+            panic!("{:?}", lrc.lines);
+        };
+        assert_eq!(line.time, ms(900));
+        assert_eq!(line.text, "Hello world a<b");
+        assert_eq!(
+            line.words,
+            vec![
+                LrcWord {
+                    time: ms(900),
+                    text: "Hello ".to_string(),
+                },
+                LrcWord {
+                    time: ms(1_400),
+                    text: "wor".to_string(),
+                },
+                LrcWord {
+                    time: ms(1_700),
+                    text: "ld a<b".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_unsynced() {
+        let content = "\n[Verse 1]\nFirst line\n\n[Chorus: Artist]\nSecond line\n\n";
+        let lrc = Lrc::parse(content);
+        assert!(!lrc.synced);
+        assert!(lrc.tags.is_empty());
+        assert_eq!(
+            lrc.lines
+                .iter()
+                .map(|l| l.text.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "[Verse 1]",
+                "First line",
+                "",
+                "[Chorus: Artist]",
+                "Second line"
+            ]
+        );
+        assert!(lrc.lines.iter().all(|l| l.time.is_zero()));
+
+        // unsynced lyrics are written without timestamps
+        assert_eq!(
+            lrc.serialize(),
+            content.trim_matches('\n').to_string() + "\n"
+        );
+        assert_eq!(Lrc::parse(""), Lrc::default());
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        let lrc = Lrc {
+            tags: vec![("ar".to_string(), "Artist".to_string())],
+            lines: vec![
+                line(ms(1_000), "First"),
+                LrcLine {
+                    time: ms(2_345),
+                    text: "Second line".to_string(),
+                    words: vec![
+                        LrcWord {
+                            time: ms(2_345),
+                            text: "Second ".to_string(),
+                        },
+                        LrcWord {
+                            time: ms(2_900),
+                            text: "line".to_string(),
+                        },
+                    ],
+                },
+                line(ms(61_230), ""),
+            ],
+            synced: true,
+        };
+
+        let content = lrc.serialize();
+        assert_eq!(
+            content,
+            "[ar:Artist]\n[00:01.00]First\n[00:02.345]<00:02.345>Second <00:02.90>line\n[01:01.23]\n"
+        );
+        assert_eq!(Lrc::parse(&content), lrc);
+    }
+
+    #[test]
+    fn serialize_enhanced_line_with_prefix() {
+        for content in [
+            "[00:01.00]Intro: <00:01.50>Hello <00:02.00>world\n",
+            "[00:01.00]Oh, <00:01.50>wor<00:02.00>ld \n",
+            "[00:01.00]<00:01.50> Hello\n",
+        ] {
+            let lrc = Lrc::parse(content);
+            assert_eq!(Lrc::parse(&lrc.serialize()), lrc, "{content}");
+        }
+
+        let lrc = Lrc::parse("[00:01.00]Intro: <00:01.50>Hello <00:02.00>world\n");
+        assert_eq!(lrc.lines[0].text, "Intro: Hello world");
+        assert_eq!(
+            lrc.serialize(),
+            "[00:01.00]Intro: <00:01.50>Hello <00:02.00>world\n"
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{matching, LyricProvider, LyricResult, Query};
+use std::time::Duration;
+
+const LRCLIB_BASE_URL: &str = "https://lrclib.net";
+
+/// A client retrieving lyrics from [LRCLIB](https://lrclib.net), which provides synced lyrics in the LRC format
+pub struct LrcLibClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl LrcLibClient {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::from_http_client(&reqwest::Client::new())
+    }
+
+    /// Construct a client reusing an existing http client
+    #[must_use]
+    pub fn from_http_client(http: &reqwest::Client) -> Self {
+        Self::with_base_url(http, LRCLIB_BASE_URL)
+    }
+
+    /// Construct a client sending requests to an LRCLIB-compatible server at `base_url`
+    #[must_use]
+    pub fn with_base_url(http: &reqwest::Client, base_url: impl Into<String>) -> Self {
+        Self {
+            http: http.clone(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Search songs satisfying a given `query`.
+    pub async fn search_songs(&self, query: &Query) -> anyhow::Result<Vec<search::Result>> {
+        let mut params = vec![("track_name", query.track.as_str())];
+        if let Some(artist) = query.artists.first() {
+            params.push(("artist_name", artist.as_str()));
+        }
+
+        log::debug!("search songs: params={params:?}");
+
+        let results = self
+            .http
+            .get(format!("{}/api/search", self.base_url))
+            .header(
+                reqwest::header::USER_AGENT,
+                concat!("lyric_finder/", env!("CARGO_PKG_VERSION")),
+            )
+            .query(&params)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Vec<search::Result>>()
+            .await?;
+        Ok(results)
+    }
+}
+
+impl Default for LrcLibClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl LyricProvider for LrcLibClient {
+    fn name(&self) -> &'static str {
+        "LRCLIB"
+    }
+
+    async fn find_lyric(&self, query: &Query) -> anyhow::Result<LyricResult> {
+        let results = self.search_songs(query).await?;
+
+        // find the best matching result, preferring synced lyrics over plain lyrics
+        let result = results
+            .iter()
+            .filter_map(|r| {
+                let (lyric, synced) = match (&r.synced_lyrics, &r.plain_lyrics) {
+                    (Some(lyric), _) => (lyric, true),
+                    (None, Some(lyric)) => (lyric, false),
+                    (None, None) => return None,
+                };
+                let duration = r
+                    .duration
+                    .filter(|d| d.is_finite() && *d >= 0.0)
+                    .map(Duration::from_secs_f64);
+                let score = matching::score(query, &r.track_name, &r.artist_name, duration);
+                (score >= matching::MIN_SCORE).then_some((synced, score, r, lyric))
+            })
+            .max_by(|(a_synced, a, ..), (b_synced, b, ..)| {
+                a_synced.cmp(b_synced).then(a.total_cmp(b))
+            });
+        let Some((synced, score, result, lyric)) = result else {
+            return Ok(LyricResult::None);
+        };
+        log::debug!("found matching song: result={result:?}, score={score}");
+
+        Ok(LyricResult::Some {
+            track: result.track_name.clone(),
+            artists: result.artist_name.clone(),
+            lyric: lyric.trim().to_string(),
+            synced,
+            source: self.name().to_string(),
+        })
+    }
+}
+
+mod search {
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Result {
+        pub track_name: String,
+        pub artist_name: String,
+        pub album_name: Option<String>,
+        /// the song's duration in seconds
+        pub duration: Option<f64>,
+        pub plain_lyrics: Option<String>,
+        /// the song's lyrics in the LRC format
+        pub synced_lyrics: Option<String>,
+    }
+}
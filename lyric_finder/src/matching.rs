@@ -0,0 +1,288 @@
+//! Scoring of search results against the searched song.
+//!
+//! Scores are between `0.0` (no match) and `1.0` (exact match).
+//! A search result scoring below [`MIN_SCORE`] is not considered as the searched song.
+
+use crate::Query;
+use std::time::Duration;
+
+/// the minimum score of a search result matching the searched song
+pub const MIN_SCORE: f64 = 0.65;
+/// the minimum title similarity of a search result matching the searched song,
+/// which rejects other songs of the same artists
+const MIN_TITLE_SCORE: f64 = 0.5;
+
+/// durations differing by at most this value are considered equal
+const DURATION_TOLERANCE: Duration = Duration::from_secs(3);
+/// durations differing by at least this value are considered unrelated
+const DURATION_MAX_DIFF: Duration = Duration::from_secs(20);
+
+/// Score a search result against a song `query`.
+///
+/// The score combines the similarity of the titles, the overlap of the artists and,
+/// if both durations are known, the closeness of the durations.
+#[must_use]
+pub fn score(query: &Query, title: &str, artists: &str, duration: Option<Duration>) -> f64 {
+    let title_score = similarity(&normalize(&query.track), &normalize(title));
+    if title_score < MIN_TITLE_SCORE {
+        return 0.0;
+    }
+    let artists_score = artists_score(&query.artists, artists);
+
+    match (query.duration, duration) {
+        (Some(expected), Some(actual)) => {
+            0.4 * title_score + 0.4 * artists_score + 0.2 * duration_score(expected, actual)
+        }
+        _ => 0.5 * title_score + 0.5 * artists_score,
+    }
+}
+
+/// Score a search result against a free-text `query` containing the song's title
+/// and possibly its artists.
+///
+/// The score is based on how many words of the result's title and artists appear in the query.
+#[must_use]
+pub fn text_score(query: &str, title: &str, artists: &str) -> f64 {
+    let query = normalize(query);
+    let query_words = query.split(' ').collect::<Vec<_>>();
+    let coverage = |s: &str| {
+        let words = normalize(s);
+        let words = words
+            .split(' ')
+            .filter(|w| !w.is_empty())
+            .collect::<Vec<_>>();
+        if words.is_empty() {
+            return 0.0;
+        }
+        let n_found = words.iter().filter(|w| query_words.contains(w)).count();
+        n_found as f64 / words.len() as f64
+    };
+
+    let title_score = coverage(title);
+    if title_score < MIN_TITLE_SCORE {
+        return 0.0;
+    }
+    // the query may not contain the artists
+    0.7 * title_score + 0.3 * coverage(artists)
+}
+
+/// Score the overlap between the `expected` artists and a result's `artists`.
+///
+/// The first (main) artist weighs more than the featured artists.
+fn artists_score(expected: &[String], artists: &str) -> f64 {
+    let Some((main, featured)) = expected.split_first() else {
+        return 1.0;
+    };
+    let artists = normalize_artists(artists);
+    let matches = |artist: &String| {
+        let artist = normalize(artist);
+        format!(" {artists} ").contains(&format!(" {artist} "))
+            || similarity(&artist, &artists) >= 0.8
+    };
+
+    let main_score = if matches(main) { 1.0 } else { 0.0 };
+    if featured.is_empty() {
+        return main_score;
+    }
+    let featured_score =
+        featured.iter().filter(|a| matches(a)).count() as f64 / featured.len() as f64;
+    0.7 * main_score + 0.3 * featured_score
+}
+
+fn duration_score(expected: Duration, actual: Duration) -> f64 {
+    let diff = expected.abs_diff(actual).as_secs_f64();
+    let tolerance = DURATION_TOLERANCE.as_secs_f64();
+    let max_diff = DURATION_MAX_DIFF.as_secs_f64();
+    ((max_diff - diff) / (max_diff - tolerance)).clamp(0.0, 1.0)
+}
+
+/// Normalize a title or a name for comparison.
+///
+/// The returned value is lowercase, without the remaster/remix information, without the
+/// parenthesized parts (e.g. "(feat. X)") and with words separated by single spaces.
+fn normalize(s: &str) -> String {
+    let s = crate::improve_query(s);
+
+    let mut normalized = String::with_capacity(s.len());
+    let mut depth = 0usize;
+    for c in s.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ if depth > 0 => {}
+            c if c.is_alphanumeric() => normalized.push(c),
+            '\'' | '’' => {}
+            _ => normalized.push(' '),
+        }
+    }
+    normalized.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Normalize a result's artists, removing the featured artists' markers
+fn normalize_artists(artists: &str) -> String {
+    normalize(artists)
+        .split(' ')
+        .filter(|w| !matches!(*w, "feat" | "ft" | "featuring" | "and" | "x"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Compute the similarity of two strings, using the Sørensen–Dice coefficient of their character bigrams
+fn similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let bigrams = |s: &str| {
+        let chars = s.chars().filter(|c| *c != ' ').collect::<Vec<_>>();
+        chars.windows(2).map(|w| (w[0], w[1])).collect::<Vec<_>>()
+    };
+    let a = bigrams(a);
+    let mut b = bigrams(b);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let total = a.len() + b.len();
+    let mut n_common = 0usize;
+    for bigram in &a {
+        if let Some(i) = b.iter().position(|x| x == bigram) {
+            b.swap_remove(i);
+            n_common += 1;
+        }
+    }
+    (2 * n_common) as f64 / total as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(track: &str, artists: &[&str], duration: Option<u64>) -> Query {
+        Query {
+            track: track.to_string(),
+            artists: artists.iter().map(|a| (*a).to_string()).collect(),
+            duration: duration.map(Duration::from_secs),
+        }
+    }
+
+    #[test]
+    fn score_table() {
+        let q = query("Shape of You", &["Ed Sheeran"], Some(234));
+        // (title, artists, duration, whether the result matches)
+        let cases = [
+            ("Shape of You", "Ed Sheeran", Some(234), true),
+            ("shape of you", "ed sheeran", None, true),
+            ("Shape of You", "Ed Sheeran", Some(236), true),
+            (
+                "Shape of You - 2017 Remastered",
+                "Ed Sheeran",
+                Some(234),
+                true,
+            ),
+            (
+                "Shape of You (feat. Stormzy)",
+                "Ed Sheeran feat. Stormzy",
+                Some(234),
+                true,
+            ),
+            ("Shape of You", "Ed Sheeran & Stormzy", Some(234), true),
+            ("Shape of You", "Some Cover Band", Some(234), false),
+            ("Shape of You", "Some Cover Band", None, false),
+            ("Perfect", "Ed Sheeran", Some(234), false),
+            ("Shape of You", "", Some(234), false),
+        ];
+        for (title, artists, duration, is_match) in cases {
+            let score = score(&q, title, artists, duration.map(Duration::from_secs));
+            assert_eq!(
+                score >= MIN_SCORE,
+                is_match,
+                "{title} by {artists} ({duration:?}): {score}"
+            );
+        }
+    }
+
+    #[test]
+    fn exact_match_scores_one() {
+        let q = query("Shape of You", &["Ed Sheeran"], Some(234));
+        let exact = score(
+            &q,
+            "Shape of You",
+            "Ed Sheeran",
+            Some(Duration::from_secs(234)),
+        );
+        assert!((exact - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn duration_mismatch_lowers_score() {
+        let q = query("Shape of You", &["Ed Sheeran"], Some(234));
+        let score_of = |secs| {
+            score(
+                &q,
+                "Shape of You",
+                "Ed Sheeran",
+                Some(Duration::from_secs(secs)),
+            )
+        };
+
+        // durations within the tolerance are equal
+        assert!((score_of(234) - score_of(237)).abs() < f64::EPSILON);
+        assert!(score_of(244) < score_of(237));
+        assert!(score_of(260) < score_of(244));
+        // an unknown duration doesn't lower the score
+        let unknown = score(&q, "Shape of You", "Ed Sheeran", None);
+        assert!(unknown > score_of(244));
+    }
+
+    #[test]
+    fn featured_artists_weigh_less() {
+        let q = query("Under Pressure", &["Queen", "David Bowie"], None);
+        let both = score(&q, "Under Pressure", "Queen & David Bowie", None);
+        let main_only = score(&q, "Under Pressure", "Queen", None);
+        let featured_only = score(&q, "Under Pressure", "David Bowie", None);
+        assert!(both > main_only);
+        assert!(main_only > featured_only);
+        assert!(main_only >= MIN_SCORE);
+    }
+
+    #[test]
+    fn text_score_table() {
+        // (query, title, artists, whether the result matches)
+        let cases = [
+            (
+                "shape of you ed sheeran",
+                "Shape of You",
+                "Ed Sheeran",
+                true,
+            ),
+            ("shape of you", "Shape of You", "Ed Sheeran", true),
+            (
+                "Shape of You - 2017 Remastered Ed Sheeran",
+                "Shape of You",
+                "Ed Sheeran",
+                true,
+            ),
+            (
+                "shape of you ed sheeran",
+                "Shape of You (Acoustic)",
+                "Ed Sheeran",
+                true,
+            ),
+            ("shape of you ed sheeran", "Perfect", "Ed Sheeran", false),
+            (
+                "shape of you ed sheeran",
+                "Castle on the Hill",
+                "Ed Sheeran",
+                false,
+            ),
+        ];
+        for (query, title, artists, is_match) in cases {
+            let score = text_score(query, title, artists);
+            assert_eq!(
+                score >= MIN_SCORE,
+                is_match,
+                "{query} / {title} by {artists}: {score}"
+            );
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use crate::LyricResult;
+
+/// A query describing the song to find the lyric of
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    pub track: String,
+    pub artists: Vec<String>,
+    /// the song's duration, used by providers that match songs by duration
+    pub duration: Option<std::time::Duration>,
+}
+
+impl Query {
+    #[must_use]
+    pub fn new(track: impl Into<String>, artists: Vec<String>) -> Self {
+        Self {
+            track: track.into(),
+            artists,
+            duration: None,
+        }
+    }
+
+    /// Returns the query as a free-text search query
+    #[must_use]
+    pub fn text(&self) -> String {
+        if self.artists.is_empty() {
+            self.track.clone()
+        } else {
+            format!("{} {}", self.track, self.artists.join(" "))
+        }
+    }
+}
+
+/// A source of song lyrics
+#[async_trait::async_trait]
+pub trait LyricProvider: Send + Sync {
+    /// The provider's display name
+    fn name(&self) -> &str;
+
+    /// Find the lyric of a song satisfying a given `query`.
+    async fn find_lyric(&self, query: &Query) -> anyhow::Result<LyricResult>;
+}
+
+/// A provider trying a list of providers in order, returning the first found lyric.
+///
+/// A provider failing to search for the lyric is skipped. If no provider finds the lyric and any provider fails,
+/// the error is returned rather than [`LyricResult::None`], as the failed provider might have found the lyric.
+pub struct ProviderChain {
+    providers: Vec<Box<dyn LyricProvider>>,
+}
+
+impl ProviderChain {
+    #[must_use]
+    pub fn new(providers: Vec<Box<dyn LyricProvider>>) -> Self {
+        Self { providers }
+    }
+}
+
+#[async_trait::async_trait]
+impl LyricProvider for ProviderChain {
+    fn name(&self) -> &'static str {
+        "chain"
+    }
+
+    async fn find_lyric(&self, query: &Query) -> anyhow::Result<LyricResult> {
+        let mut error = None;
+
+        for provider in &self.providers {
+            match provider.find_lyric(query).await {
+                Ok(LyricResult::None) => {}
+                Ok(result) => return Ok(result),
+                Err(err) => {
+                    log::warn!("failed to find lyric with {}: {err:#}", provider.name());
+                    error = Some(err);
+                }
+            }
+        }
+
+        match error {
+            Some(err) => Err(err),
+            None => Ok(LyricResult::None),
+        }
+    }
+}
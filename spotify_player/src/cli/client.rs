@@ -4,27 +4,36 @@ use std::{
     fs::{create_dir_all, remove_dir_all},
     io::Write,
     net::SocketAddr,
+    path::Path,
 };
 
 use anyhow::{Context as _, Result};
 use rand::seq::SliceRandom;
-use tokio::net::UdpSocket;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite},
+    net::UdpSocket,
+};
 use tracing::Instrument;
 
 use crate::{
-    cli::Request,
-    client::{AppClient, PlayerRequest},
-    config::get_cache_folder_path,
+    cli::{
+        transport::{self, RequestFrame, ResponseFrame, UdpRequest},
+        Request,
+    },
+    client::{AppClient, ClientRequest, PlayerRequest},
+    config::{self, get_cache_folder_path},
     state::{
-        AlbumId, ArtistId, Context, ContextId, Id, PlayableId, Playback, PlaybackMetadata,
-        PlaylistId, SharedState, TrackId,
+        AlbumId, ArtistId, Context, ContextId, EpisodeId, Id, PlayableId, Playback,
+        PlaybackMetadata, PlaylistId, SharedState, TrackId,
     },
 };
 use rspotify::prelude::{BaseClient, OAuthClient};
 
 use super::{
-    Command, Deserialize, EditAction, GetRequest, IdOrName, ItemId, ItemType, Key, PlaylistCommand,
-    Response, Serialize, MAX_REQUEST_SIZE,
+    format::{format_output, OutputFormat, Table},
+    Command, Deserialize, EditAction, GetRequest, IdOrName, ItemId, ItemType, Key, LyricsCommand,
+    PlaylistCommand, PlaylistFileFormat, QueueCommand, QueueItemType, Response, Serialize,
+    MAX_REQUEST_SIZE,
 };
 
 pub async fn start_socket(client: AppClient, socket: UdpSocket, state: Option<SharedState>) {
@@ -41,26 +50,23 @@ pub async fn start_socket(client: AppClient, socket: UdpSocket, state: Option<Sh
                 }
 
                 let req_buf = &buf[0..n_bytes];
-                let request: Request = match serde_json::from_slice(req_buf) {
-                    Ok(v) => v,
-                    Err(err) => {
-                        tracing::error!("Cannot deserialize the socket request: {err:#}");
-                        continue;
-                    }
+                // requests without a token are sent as plain requests
+                let (token, request) = match serde_json::from_slice::<UdpRequest>(req_buf) {
+                    Ok(v) => (Some(v.token), v.request),
+                    Err(_) => match serde_json::from_slice::<Request>(req_buf) {
+                        Ok(v) => (None, v),
+                        Err(err) => {
+                            tracing::error!("Cannot deserialize the socket request: {err:#}");
+                            continue;
+                        }
+                    },
                 };
 
                 let span = tracing::info_span!("socket_request", request = ?request, dest_addr = ?dest_addr);
 
                 async {
                     let response =
-                        match handle_socket_request(&client, state.as_ref(), request).await {
-                            Err(err) => {
-                                tracing::error!("Failed to handle socket request: {err:#}");
-                                let msg = format!("Bad request: {err:#}");
-                                Response::Err(msg.into_bytes())
-                            }
-                            Ok(data) => Response::Ok(data),
-                        };
+                        handle_request(&client, state.as_ref(), token.as_deref(), request).await;
                     send_response(response, &socket, dest_addr)
                         .await
                         .unwrap_or_default();
@@ -74,6 +80,195 @@ pub async fn start_socket(client: AppClient, socket: UdpSocket, state: Option<Sh
     }
 }
 
+#[cfg(unix)]
+/// starts a Unix domain socket listener handling framed CLI requests
+pub async fn start_unix_socket(
+    client: AppClient,
+    listener: tokio::net::UnixListener,
+    state: Option<SharedState>,
+) {
+    loop {
+        match listener.accept().await {
+            Err(err) => tracing::warn!("Failed to accept a Unix socket connection: {err:#}"),
+            Ok((stream, _)) => {
+                tokio::task::spawn(handle_stream(client.clone(), stream, state.clone()));
+            }
+        }
+    }
+}
+
+/// starts a TCP listener handling framed CLI requests
+pub async fn start_tcp_socket(
+    client: AppClient,
+    listener: tokio::net::TcpListener,
+    state: Option<SharedState>,
+) {
+    loop {
+        match listener.accept().await {
+            Err(err) => tracing::warn!("Failed to accept a TCP connection: {err:#}"),
+            Ok((stream, addr)) => {
+                if !addr.ip().is_loopback() {
+                    tracing::warn!("Rejected a TCP connection from a non-loopback address {addr}");
+                    continue;
+                }
+                tokio::task::spawn(handle_stream(client.clone(), stream, state.clone()));
+            }
+        }
+    }
+}
+
+/// handles framed CLI requests from a stream connection until it is closed
+async fn handle_stream<S>(client: AppClient, stream: S, state: Option<SharedState>)
+where
+    S: AsyncRead + AsyncWrite,
+{
+    let (mut reader, mut writer) = tokio::io::split(stream);
+    loop {
+        let frame: RequestFrame = match transport::read_frame(&mut reader).await {
+            Ok(Some(frame)) => frame,
+            Ok(None) => return,
+            Err(err) => {
+                tracing::error!("Cannot read the socket request: {err:#}");
+                return;
+            }
+        };
+
+        let span = tracing::info_span!("socket_request", id = frame.id, request = ?frame.request);
+
+        if let Request::Subscribe = frame.request {
+            // the subscription holds the connection until it is closed
+            let token = frame.token.as_deref();
+            handle_subscription(frame.id, state.as_ref(), token, &mut reader, &mut writer)
+                .instrument(span)
+                .await;
+            return;
+        }
+
+        let result = async {
+            let token = frame.token.as_deref();
+            let response = handle_request(&client, state.as_ref(), token, frame.request).await;
+            let frame = ResponseFrame {
+                id: frame.id,
+                response,
+            };
+            transport::write_frame(&mut writer, &frame).await?;
+
+            tracing::info!("Successfully handled the socket request.");
+            anyhow::Ok(())
+        }
+        .instrument(span)
+        .await;
+
+        if let Err(err) = result {
+            tracing::warn!("Failed to send the socket response: {err:#}");
+            return;
+        }
+    }
+}
+
+/// streams playback events to a subscriber, each as a response to the subscription request,
+/// until the connection is closed
+async fn handle_subscription<R, W>(
+    id: u64,
+    state: Option<&SharedState>,
+    token: Option<&str>,
+    reader: &mut R,
+    writer: &mut W,
+) where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let result = authorize(token, &Request::Subscribe)
+        .and_then(|()| state.context("No running client to subscribe to"));
+    let state = match result {
+        Ok(state) => state,
+        Err(err) => {
+            tracing::error!("Failed to subscribe to playback events: {err:#}");
+            let response = Response::Err(format!("Bad request: {err:#}").into_bytes());
+            transport::write_frame(writer, &ResponseFrame { id, response })
+                .await
+                .unwrap_or_default();
+            return;
+        }
+    };
+
+    tracing::info!("Started a playback events subscription.");
+    let mut events = state.events.subscribe();
+    let mut buf = [0; 1];
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
+                        tracing::warn!("Dropped {n} playback events of a lagging subscriber");
+                        continue;
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                };
+                let response = match serde_json::to_vec(&event) {
+                    Ok(data) => Response::Ok(data),
+                    Err(err) => {
+                        tracing::error!("Failed to serialize a playback event: {err:#}");
+                        continue;
+                    }
+                };
+                if let Err(err) = transport::write_frame(writer, &ResponseFrame { id, response }).await {
+                    tracing::warn!("Failed to send a playback event: {err:#}");
+                    break;
+                }
+            }
+            // the subscriber doesn't send anything after the subscription request,
+            // so a read only returns when the connection is closed
+            _ = reader.read(&mut buf) => break,
+        }
+    }
+    tracing::info!("Stopped a playback events subscription.");
+}
+
+/// checks that a CLI request is authorized by a configured token with the request's scope.
+///
+/// Requests are always authorized if no token is configured.
+fn authorize(token: Option<&str>, request: &Request) -> Result<()> {
+    let config = &config::get_config().cli_tokens_config;
+    if config.tokens.is_empty() {
+        return Ok(());
+    }
+
+    let token = token.context("Unauthorized: the request has no token")?;
+    let token = config
+        .find(token)
+        .context("Unauthorized: the request's token is invalid")?;
+    let scope = request.scope();
+    anyhow::ensure!(
+        token.scopes.contains(&scope),
+        "Forbidden: the token \"{}\" doesn't have the \"{scope}\" scope",
+        token.name
+    );
+    Ok(())
+}
+
+/// handles a CLI request, converting an error into an error response
+pub async fn handle_request(
+    client: &AppClient,
+    state: Option<&SharedState>,
+    token: Option<&str>,
+    request: Request,
+) -> Response {
+    let result = match authorize(token, &request) {
+        Ok(()) => handle_socket_request(client, state, request).await,
+        Err(err) => Err(err),
+    };
+    match result {
+        Err(err) => {
+            tracing::error!("Failed to handle socket request: {err:#}");
+            let msg = format!("Bad request: {err:#}");
+            Response::Err(msg.into_bytes())
+        }
+        Ok(data) => Response::Ok(data),
+    }
+}
+
 async fn send_response(
     response: Response,
     socket: &UdpSocket,
@@ -115,10 +310,14 @@ async fn handle_socket_request(
     }
 
     match request {
-        Request::Get(GetRequest::Key(key)) => handle_get_key_request(client, state, key).await,
-        Request::Get(GetRequest::Item(item_type, id_or_name)) => {
-            handle_get_item_request(client, item_type, id_or_name).await
-        }
+        Request::Get {
+            request: GetRequest::Key(key),
+            format,
+        } => handle_get_key_request(client, state, key, &format).await,
+        Request::Get {
+            request: GetRequest::Item(item_type, id_or_name),
+            format,
+        } => handle_get_item_request(client, item_type, id_or_name, &format).await,
         Request::Playback(command) => {
             handle_playback_request(client, state, command).await?;
             Ok(Vec::new())
@@ -167,13 +366,32 @@ async fn handle_socket_request(
             Ok(Vec::new())
         }
         Request::Playlist(command) => {
-            let resp = handle_playlist_request(client, command).await?;
+            let resp = handle_playlist_request(client, state, command).await?;
             Ok(resp.into_bytes())
         }
-        Request::Search { query } => {
-            let resp = handle_search_request(client, query).await?;
+        Request::Lyrics(LyricsCommand::Export {
+            playlist_id,
+            album_id,
+            dir,
+        }) => {
+            let resp = lyrics_export(client, state, playlist_id, album_id, &dir).await?;
+            Ok(resp.into_bytes())
+        }
+        Request::Queue(QueueCommand::Add(item_type, id_or_name)) => {
+            handle_queue_add_request(client, state, item_type, id_or_name).await?;
+            Ok(Vec::new())
+        }
+        Request::Queue(QueueCommand::List(format)) => {
+            let queue = client.current_user_queue().await?;
+            format_output(&queue, &format, Table::queue)
+        }
+        Request::Search { query, format } => {
+            let resp = handle_search_request(client, query, &format).await?;
             Ok(resp)
         }
+        Request::Subscribe => {
+            anyhow::bail!("Subscribing to events requires a Unix or TCP socket connection")
+        }
     }
 }
 
@@ -181,41 +399,42 @@ async fn handle_get_key_request(
     client: &AppClient,
     state: Option<&SharedState>,
     key: Key,
+    format: &OutputFormat,
 ) -> Result<Vec<u8>> {
-    Ok(match key {
+    match key {
         Key::Playback => {
             let playback = current_playback(client, state).await?;
-            serde_json::to_vec(&playback)?
+            format_output(&playback, format, |p| Table::playback(p.as_ref()))
         }
         Key::Devices => {
             let devices = client.available_devices().await?;
-            serde_json::to_vec(&devices)?
+            format_output(&devices, format, |d| Table::devices(d))
         }
         Key::UserPlaylists => {
             let playlists = client.current_user_playlists().await?;
-            serde_json::to_vec(&playlists)?
+            format_output(&playlists, format, |p| Table::playlists(p))
         }
         Key::UserLikedTracks => {
             let tracks = client.current_user_saved_tracks().await?;
-            serde_json::to_vec(&tracks)?
+            format_output(&tracks, format, |t| Table::tracks(t))
         }
         Key::UserTopTracks => {
             let tracks = client.current_user_top_tracks().await?;
-            serde_json::to_vec(&tracks)?
+            format_output(&tracks, format, |t| Table::tracks(t))
         }
         Key::UserSavedAlbums => {
             let albums = client.current_user_saved_albums().await?;
-            serde_json::to_vec(&albums)?
+            format_output(&albums, format, |a| Table::albums(a))
         }
         Key::UserFollowedArtists => {
             let artists = client.current_user_followed_artists().await?;
-            serde_json::to_vec(&artists)?
+            format_output(&artists, format, |a| Table::artists(a))
         }
         Key::Queue => {
             let queue = client.current_user_queue().await?;
-            serde_json::to_vec(&queue)?
+            format_output(&queue, format, Table::queue)
         }
-    })
+    }
 }
 
 /// Get a Spotify item's ID from its `IdOrName` representation
@@ -303,24 +522,96 @@ async fn get_spotify_id(client: &AppClient, typ: ItemType, id_or_name: IdOrName)
     Ok(sid)
 }
 
+async fn handle_queue_add_request(
+    client: &AppClient,
+    state: Option<&SharedState>,
+    item_type: QueueItemType,
+    id_or_name: IdOrName,
+) -> Result<()> {
+    let request = match item_type {
+        QueueItemType::Track => {
+            let ItemId::Track(id) = get_spotify_id(client, ItemType::Track, id_or_name).await?
+            else {
+                anyhow::bail!("Unable to get track id")
+            };
+            ClientRequest::AddPlayableToQueue(PlayableId::Track(id))
+        }
+        QueueItemType::Album => {
+            let ItemId::Album(id) = get_spotify_id(client, ItemType::Album, id_or_name).await?
+            else {
+                anyhow::bail!("Unable to get album id")
+            };
+            ClientRequest::AddAlbumToQueue(id)
+        }
+        QueueItemType::Episode => {
+            let id = match id_or_name {
+                IdOrName::Id(id) => EpisodeId::from_id(id)?,
+                IdOrName::Name(name) => {
+                    let results = client
+                        .search_specific_type(&name, rspotify::model::SearchType::Episode)
+                        .await?;
+
+                    match results {
+                        rspotify::model::SearchResult::Episodes(page) => match page.items.first() {
+                            Some(episode) => episode.id.clone(),
+                            None => anyhow::bail!("Cannot find episode with name='{name}'"),
+                        },
+                        _ => unreachable!(),
+                    }
+                }
+            };
+            ClientRequest::AddPlayableToQueue(PlayableId::Episode(id))
+        }
+    };
+
+    match state {
+        Some(state) => {
+            client.handle_request(state, request).await?;
+            // update the application's queue to reflect the added items
+            client
+                .handle_request(state, ClientRequest::GetCurrentUserQueue)
+                .await?;
+        }
+        None => match request {
+            ClientRequest::AddPlayableToQueue(id) => client.add_item_to_queue(id, None).await?,
+            ClientRequest::AddAlbumToQueue(id) => client.add_album_to_queue(id).await?,
+            _ => unreachable!(),
+        },
+    }
+    Ok(())
+}
+
 async fn handle_get_item_request(
     client: &AppClient,
     item_type: ItemType,
     id_or_name: IdOrName,
+    format: &OutputFormat,
 ) -> Result<Vec<u8>> {
     let sid = get_spotify_id(client, item_type, id_or_name).await?;
-    Ok(match sid {
-        ItemId::Playlist(id) => serde_json::to_vec(&client.playlist_context(id).await?)?,
-        ItemId::Album(id) => serde_json::to_vec(&client.album_context(id).await?)?,
-        ItemId::Artist(id) => serde_json::to_vec(&client.artist_context(id).await?)?,
-        ItemId::Track(id) => serde_json::to_vec(&client.track(id).await?)?,
-    })
+    match sid {
+        ItemId::Playlist(id) => {
+            format_output(&client.playlist_context(id).await?, format, Table::context)
+        }
+        ItemId::Album(id) => {
+            format_output(&client.album_context(id).await?, format, Table::context)
+        }
+        ItemId::Artist(id) => {
+            format_output(&client.artist_context(id).await?, format, Table::context)
+        }
+        ItemId::Track(id) => format_output(&client.track(id).await?, format, |t| {
+            Table::tracks(std::slice::from_ref(t))
+        }),
+    }
 }
 
-async fn handle_search_request(client: &AppClient, query: String) -> Result<Vec<u8>> {
+async fn handle_search_request(
+    client: &AppClient,
+    query: String,
+    format: &OutputFormat,
+) -> Result<Vec<u8>> {
     let search_result = client.search(&query).await?;
 
-    Ok(serde_json::to_vec(&search_result)?)
+    format_output(&search_result, format, Table::search_results)
 }
 
 async fn handle_playback_request(
@@ -465,7 +756,11 @@ async fn handle_playback_request(
     Ok(())
 }
 
-async fn handle_playlist_request(client: &AppClient, command: PlaylistCommand) -> Result<String> {
+async fn handle_playlist_request(
+    client: &AppClient,
+    state: Option<&SharedState>,
+    command: PlaylistCommand,
+) -> Result<String> {
     let uid = client.current_user().await?.id;
 
     match command {
@@ -523,6 +818,10 @@ async fn handle_playlist_request(client: &AppClient, command: PlaylistCommand) -
             to: import_to,
             delete,
         } => playlist_import(client, import_from, import_to, delete).await,
+        PlaylistCommand::ImportFile { file, to } => playlist_import_file(client, &file, to).await,
+        PlaylistCommand::Export { id, format, output } => {
+            playlist_export(client, state, id, format, output.as_deref()).await
+        }
         PlaylistCommand::Fork { id } => {
             let from = client
                 .playlist(id.clone(), None, None)
@@ -710,20 +1009,28 @@ async fn playlist_import(
     // Get playlists' info
     let (from_tracks, from_name) = match client.playlist_context(import_from.clone()).await? {
         Context::Playlist { tracks, playlist } => (
-            tracks.into_iter().map(|t| TrackData {
-                id: t.id,
-                name: t.name,
-            }),
+            // local file entries can't be added to a playlist by their IDs
+            tracks
+                .into_iter()
+                .filter(|t| !t.is_local)
+                .map(|t| TrackData {
+                    id: t.id,
+                    name: t.name,
+                }),
             playlist.name,
         ),
         _ => unreachable!(),
     };
     let (to_tracks, to_name) = match client.playlist_context(import_to.clone()).await? {
         Context::Playlist { tracks, playlist } => (
-            tracks.into_iter().map(|t| TrackData {
-                id: t.id,
-                name: t.name,
-            }),
+            // local file entries can't be added to a playlist by their IDs
+            tracks
+                .into_iter()
+                .filter(|t| !t.is_local)
+                .map(|t| TrackData {
+                    id: t.id,
+                    name: t.name,
+                }),
             playlist.name,
         ),
         _ => unreachable!(),
@@ -842,3 +1149,266 @@ async fn playlist_import(
 
     Ok(result)
 }
+
+/// Imports a playlist file into a playlist.
+///
+/// Each file entry is matched with a Spotify track by its Spotify URI or, if the entry
+/// is not located by a Spotify URI, by searching the entry's title.
+/// Matched tracks that are already in the `import_to` playlist are skipped.
+async fn playlist_import_file(
+    client: &AppClient,
+    file: &Path,
+    import_to: PlaylistId<'static>,
+) -> Result<String> {
+    let playlist = local_player::playlist::Playlist::load(file)?;
+
+    let (to_tracks, to_name) = match client.playlist_context(import_to.clone()).await? {
+        Context::Playlist { tracks, playlist } => (
+            tracks
+                .into_iter()
+                .filter(|t| !t.is_local)
+                .map(|t| t.id)
+                .collect::<HashSet<_>>(),
+            playlist.name,
+        ),
+        _ => unreachable!(),
+    };
+
+    let mut result = String::new();
+    writeln!(
+        result,
+        "Importing from {} to {}:{}...",
+        file.display(),
+        import_to.id(),
+        to_name
+    )
+    .unwrap();
+
+    let mut new_tracks = Vec::new();
+    let mut not_found = Vec::new();
+    for entry in &playlist.entries {
+        let track = match TrackId::from_uri(&entry.location) {
+            Ok(id) => Some((id.into_static(), entry.location.clone())),
+            Err(_) => search_playlist_entry(client, entry).await?,
+        };
+        match track {
+            Some((id, name)) => {
+                if !to_tracks.contains(&id) && !new_tracks.iter().any(|(i, _)| *i == id) {
+                    new_tracks.push((id, name));
+                }
+            }
+            None => not_found.push(entry),
+        }
+    }
+
+    writeln!(result, "New tracks imported to {to_name}: ").unwrap();
+    for chunk in new_tracks.chunks(TRACK_BUFFER_CAP) {
+        client
+            .playlist_add_items(
+                import_to.as_ref(),
+                chunk.iter().map(|(id, _)| PlayableId::Track(id.as_ref())),
+                None,
+            )
+            .await?;
+        for (id, name) in chunk {
+            writeln!(result, "    {}: {name}", id.id()).unwrap();
+        }
+    }
+
+    if !not_found.is_empty() {
+        writeln!(result, "Entries without a matching track: ").unwrap();
+        for entry in not_found {
+            let (artists, title) = entry.artists_and_title();
+            match artists {
+                Some(artists) => writeln!(result, "    {artists} - {title}").unwrap(),
+                None => writeln!(result, "    {title}").unwrap(),
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Searches the Spotify track matching a playlist file entry, returning the track's ID and name
+async fn search_playlist_entry(
+    client: &AppClient,
+    entry: &local_player::playlist::PlaylistEntry,
+) -> Result<Option<(TrackId<'static>, String)>> {
+    let (artists, title) = entry.artists_and_title();
+    if title.is_empty() {
+        return Ok(None);
+    }
+    let query = match artists {
+        Some(artists) => format!("track:{title} artist:{artists}"),
+        None => title.to_string(),
+    };
+
+    match client
+        .search_specific_type(&query, rspotify::model::SearchType::Track)
+        .await?
+    {
+        rspotify::model::SearchResult::Tracks(page) => {
+            // pick the best result matching the entry, using the same scoring as lyrics search
+            let query = lyric_finder::Query {
+                track: title.to_string(),
+                artists: artists
+                    .map(|a| a.split(", ").map(str::to_string).collect())
+                    .unwrap_or_default(),
+                duration: entry.duration,
+            };
+            Ok(page
+                .items
+                .into_iter()
+                .filter_map(|t| {
+                    let score = lyric_finder::matching::score(
+                        &query,
+                        &t.name,
+                        &crate::utils::map_join(&t.artists, |a| &a.name, ", "),
+                        t.duration.to_std().ok(),
+                    );
+                    Some((score, t.id?, t.name))
+                })
+                .filter(|(score, ..)| *score >= lyric_finder::matching::MIN_SCORE)
+                // prefer the first result among equally scored results
+                .reduce(|best, r| if r.0 > best.0 { r } else { best })
+                .map(|(_, id, name)| (id, name)))
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Exports a playlist into a playlist file.
+///
+/// Tracks are located by their Spotify URIs. Local file entries are located by their
+/// audio files if found in the running application's local library, otherwise they are skipped.
+/// Returns the playlist file's content if no output file is specified.
+async fn playlist_export(
+    client: &AppClient,
+    state: Option<&SharedState>,
+    id: PlaylistId<'static>,
+    format: PlaylistFileFormat,
+    output: Option<&Path>,
+) -> Result<String> {
+    let (mut tracks, name) = match client.playlist_context(id.clone()).await? {
+        Context::Playlist { tracks, playlist } => (tracks, playlist.name),
+        _ => unreachable!(),
+    };
+    if let Some(state) = state {
+        state.data.read().local_library.resolve_tracks(&mut tracks);
+    }
+
+    let mut skipped = 0;
+    let entries = tracks
+        .iter()
+        .filter_map(|t| {
+            let location = if t.is_local {
+                let Some(path) = &t.local_path else {
+                    skipped += 1;
+                    return None;
+                };
+                path.display().to_string()
+            } else {
+                t.id.uri()
+            };
+            let artists = t.artists_info();
+            Some(local_player::playlist::PlaylistEntry {
+                location,
+                title: Some(if artists.is_empty() {
+                    t.name.clone()
+                } else {
+                    format!("{artists} - {}", t.name)
+                }),
+                duration: Some(t.duration),
+            })
+        })
+        .collect::<Vec<_>>();
+    let n_entries = entries.len();
+    let playlist = local_player::playlist::Playlist {
+        name: Some(name.clone()),
+        entries,
+    };
+
+    let Some(output) = output else {
+        return Ok(playlist.serialize(format.into()));
+    };
+    std::fs::write(output, playlist.serialize(format.into()))
+        .with_context(|| format!("write {}", output.display()))?;
+
+    let mut result = format!(
+        "Exported {n_entries} tracks of {}:{name} to {}.",
+        id.id(),
+        output.display()
+    );
+    if skipped > 0 {
+        write!(
+            result,
+            "\nSkipped {skipped} local files not found in the local library."
+        )
+        .unwrap();
+    }
+    Ok(result)
+}
+
+/// Exports the lyrics of a playlist's or an album's tracks into a directory.
+///
+/// Lyrics are retrieved through the lyrics pipeline, i.e. from the local lyrics files,
+/// the lyrics cache, Spotify or the lyrics providers.
+/// Returns a report listing the tracks without lyrics.
+async fn lyrics_export(
+    client: &AppClient,
+    state: Option<&SharedState>,
+    playlist_id: Option<PlaylistId<'static>>,
+    album_id: Option<AlbumId<'static>>,
+    dir: &Path,
+) -> Result<String> {
+    let (mut tracks, uri, name) = match (playlist_id, album_id) {
+        (Some(id), _) => match client.playlist_context(id.clone()).await? {
+            Context::Playlist { tracks, playlist } => (tracks, id.uri(), playlist.name),
+            _ => unreachable!(),
+        },
+        (None, Some(id)) => match client.album_context(id.clone()).await? {
+            Context::Album { tracks, album } => (tracks, id.uri(), album.name),
+            _ => unreachable!(),
+        },
+        (None, None) => anyhow::bail!("either a playlist or an album must be specified"),
+    };
+    if let Some(state) = state {
+        state.data.read().local_library.resolve_tracks(&mut tracks);
+    }
+    create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+
+    let mut n_exported = 0;
+    let mut missing = vec![];
+    for track in &tracks {
+        let lyrics = match client.lyrics(track).await {
+            Ok(Some(lyrics)) => lyrics,
+            Ok(None) => {
+                missing.push(format!("{} - {}", track.artists_info(), track.name));
+                continue;
+            }
+            Err(err) => {
+                tracing::warn!("Failed to get lyrics of {track}: {err:#}");
+                missing.push(format!("{} - {}", track.artists_info(), track.name));
+                continue;
+            }
+        };
+
+        let path = dir.join(crate::lyrics::export_file_name(track, &lyrics));
+        std::fs::write(&path, crate::lyrics::export_content(track, &lyrics))
+            .with_context(|| format!("write {}", path.display()))?;
+        n_exported += 1;
+    }
+
+    let mut result = format!(
+        "Exported lyrics of {n_exported}/{} tracks of {uri}:{name} to {}.",
+        tracks.len(),
+        dir.display()
+    );
+    if !missing.is_empty() {
+        write!(result, "\nNo lyrics found for {} tracks:", missing.len()).unwrap();
+        for track in missing {
+            write!(result, "\n- {track}").unwrap();
+        }
+    }
+    Ok(result)
+}
@@ -1,9 +1,12 @@
 use clap::{builder::EnumValueParser, value_parser, Arg, ArgAction, ArgGroup, Command};
 use clap_complete::Shell;
+use std::path::PathBuf;
 
 use crate::cli::EditAction;
 
-use super::{ContextType, ItemType, Key};
+use super::{
+    format::OutputFormatKind, ContextType, ItemType, Key, PlaylistFileFormat, QueueItemType,
+};
 
 pub fn init_connect_subcommand() -> Command {
     add_id_or_name_group(Command::new("connect").about("Connect to a Spotify device"))
@@ -13,19 +16,23 @@ pub fn init_get_subcommand() -> Command {
     Command::new("get")
         .about("Get Spotify data")
         .subcommand_required(true)
-        .subcommand(
+        .subcommand(add_format_args(
             Command::new("key").about("Get data by key").arg(
                 Arg::new("key")
                     .value_parser(EnumValueParser::<Key>::new())
                     .required(true),
             ),
-        )
-        .subcommand(add_id_or_name_group(
-            Command::new("item").about("Get a Spotify item's data").arg(
-                Arg::new("item_type")
-                    .value_parser(EnumValueParser::<ItemType>::new())
-                    .required(true),
+            "json",
+        ))
+        .subcommand(add_format_args(
+            add_id_or_name_group(
+                Command::new("item").about("Get a Spotify item's data").arg(
+                    Arg::new("item_type")
+                        .value_parser(EnumValueParser::<ItemType>::new())
+                        .required(true),
+                ),
             ),
+            "json",
         ))
 }
 
@@ -80,6 +87,25 @@ fn init_playback_start_subcommand() -> Command {
         ))
 }
 
+fn init_playback_queue_subcommand() -> Command {
+    Command::new("queue")
+        .about("Manage the playback queue")
+        .subcommand_required(true)
+        .subcommand(add_id_or_name_group(
+            Command::new("add")
+                .about("Add a track, an album's tracks or an episode to the queue")
+                .arg(
+                    Arg::new("item_type")
+                        .value_parser(EnumValueParser::<QueueItemType>::new())
+                        .required(true),
+                ),
+        ))
+        .subcommand(add_format_args(
+            Command::new("list").about("List the tracks and episodes in the queue"),
+            "table",
+        ))
+}
+
 fn add_id_or_name_group(cmd: Command) -> Command {
     cmd.arg(Arg::new("id").long("id").short('i'))
         .arg(Arg::new("name").long("name").short('n'))
@@ -90,11 +116,29 @@ fn add_id_or_name_group(cmd: Command) -> Command {
         )
 }
 
+fn add_format_args(cmd: Command, default_format: &'static str) -> Command {
+    cmd.arg(
+        Arg::new("format")
+            .long("format")
+            .short('f')
+            .value_parser(EnumValueParser::<OutputFormatKind>::new())
+            .default_value(default_format)
+            .help("Output format"),
+    )
+    .arg(
+        Arg::new("template")
+            .long("template")
+            .required_if_eq("format", "template")
+            .help("Output template with column placeholders, e.g. \"{track} - {artists}\""),
+    )
+}
+
 pub fn init_playback_subcommand() -> Command {
     Command::new("playback")
         .about("Interact with the playback")
         .subcommand_required(true)
         .subcommand(init_playback_start_subcommand())
+        .subcommand(init_playback_queue_subcommand())
         .subcommand(Command::new("play-pause").about("Toggle between play and pause"))
         .subcommand(Command::new("play").about("Resume the current playback if stopped"))
         .subcommand(Command::new("pause").about("Pause the current playback if playing"))
@@ -129,9 +173,12 @@ pub fn init_playback_subcommand() -> Command {
 }
 
 pub fn init_search_command() -> Command {
-    Command::new("search")
-        .about("Search spotify")
-        .arg(Arg::new("query").help("Search query").required(true))
+    add_format_args(
+        Command::new("search")
+            .about("Search spotify")
+            .arg(Arg::new("query").help("Search query").required(true)),
+        "json",
+    )
 }
 
 pub fn init_like_command() -> Command {
@@ -146,6 +193,23 @@ pub fn init_like_command() -> Command {
         )
 }
 
+pub fn init_events_command() -> Command {
+    Command::new("events")
+        .about("Print playback events as JSON lines")
+        .arg(
+            Arg::new("follow")
+                .long("follow")
+                .short('f')
+                .action(ArgAction::SetTrue)
+                .help("Keep printing events instead of exiting after the first event"),
+        )
+        .after_help(
+            "Events are `track_changed`, `play_pause`, `volume_changed`, `shuffle_changed`, \
+             `repeat_changed`, `device_changed` and `queue_changed`. \
+             Requires a running `spotify_player` instance.",
+        )
+}
+
 pub fn init_authenticate_command() -> Command {
     Command::new("authenticate").about("Authenticate the application")
 }
@@ -185,17 +249,43 @@ pub fn init_playlist_subcommand() -> Command {
         .subcommand(Command::new("delete").about("Delete a playlist")
             .arg(Arg::new("id")
                 .value_parser(clap::builder::NonEmptyStringValueParser::new())))
-        .subcommand(Command::new("import").about("Imports all songs from a playlist into another playlist.")
+        .subcommand(Command::new("import").about("Imports all songs from a playlist or a playlist file into another playlist.")
+            .allow_missing_positional(true)
             .arg(Arg::new("from")
+                .required_unless_present("file")
+                .conflicts_with("file")
                 .value_parser(clap::builder::NonEmptyStringValueParser::new()))
             .arg(Arg::new("to")
+                .required(true)
                 .value_parser(clap::builder::NonEmptyStringValueParser::new()))
+            .arg(Arg::new("file")
+                .short('f')
+                .long("file")
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .help("Imports tracks from a M3U/M3U8/PLS playlist file instead of a playlist. Each entry is matched with a Spotify track by its Spotify URI or by searching its title."))
             .arg(Arg::new("delete")
                 .short('d')
                 .long("delete")
+                .conflicts_with("file")
                 .action(clap::ArgAction::SetTrue)
                 .help("Deletes any previously imported tracks that are no longer in the imported playlist since last import."))
             .after_help("Import data for each playlist is stored inside the application's cache folder. If imported again, the command only imports new tracks since last import."))
+        .subcommand(Command::new("export").about("Exports a playlist into a playlist file.")
+            .arg(Arg::new("id")
+                .required(true)
+                .value_parser(clap::builder::NonEmptyStringValueParser::new()))
+            .arg(Arg::new("format")
+                .long("format")
+                .value_parser(EnumValueParser::<PlaylistFileFormat>::new())
+                .default_value("m3u")
+                .help("Format of the playlist file. M3U playlists are written in the extended M3U8 form"))
+            .arg(Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("FILE")
+                .value_parser(value_parser!(PathBuf))
+                .help("Path to the output file. If not specified, the playlist file's content is printed")))
         .subcommand(Command::new("list").about("Lists all user playlists."))
         .subcommand(Command::new("fork").about("Creates a copy of a playlist and imports it.")
             .arg(Arg::new("id")
@@ -234,3 +324,32 @@ pub fn init_playlist_subcommand() -> Command {
                     .required(true)
             ))
 }
+
+pub fn init_lyrics_subcommand() -> Command {
+    Command::new("lyrics")
+        .about("Lyrics exporting")
+        .subcommand_required(true)
+        .subcommand(Command::new("export").about("Exports the lyrics of a playlist's or an album's tracks into LRC files.")
+            .arg(Arg::new("playlist_id")
+                .long("playlist")
+                .value_name("ID")
+                .help("Playlist ID")
+                .value_parser(clap::builder::NonEmptyStringValueParser::new()))
+            .arg(Arg::new("album_id")
+                .long("album")
+                .value_name("ID")
+                .help("Album ID")
+                .value_parser(clap::builder::NonEmptyStringValueParser::new()))
+            .arg(Arg::new("dir")
+                .long("dir")
+                .value_name("DIR")
+                .required(true)
+                .value_parser(value_parser!(PathBuf))
+                .help("Directory of the exported lyrics files"))
+            .group(
+                ArgGroup::new("context_id")
+                    .args(["playlist_id", "album_id"])
+                    .required(true)
+            )
+            .after_help("Synced lyrics are written into `<artists> - <title>.lrc` files, and unsynced lyrics into `<artists> - <title>.txt` files."))
+}
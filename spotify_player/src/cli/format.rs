@@ -0,0 +1,387 @@
+//! Output formats of the `get` and `search` CLI commands.
+//!
+//! Besides JSON, the commands' data is converted into a table of named columns,
+//! which can be printed as an aligned table, as CSV, or with a template.
+
+use std::sync::LazyLock;
+
+use anyhow::Result;
+use rspotify::model::{CurrentPlaybackContext, CurrentUserQueue, Device, PlayableItem};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config,
+    state::{Album, Artist, Context, Episode, Id, Playlist, SearchResults, Track},
+    utils::{format_duration, map_join},
+};
+
+const TRACK_COLUMNS: &[&str] = &["id", "track", "artists", "album", "duration"];
+const ALBUM_COLUMNS: &[&str] = &["id", "album", "artists", "release_date"];
+const ARTIST_COLUMNS: &[&str] = &["id", "artist"];
+const PLAYLIST_COLUMNS: &[&str] = &["id", "playlist", "owner"];
+const DEVICE_COLUMNS: &[&str] = &["id", "device", "type", "volume", "is_active"];
+const PLAYBACK_COLUMNS: &[&str] = &[
+    "status", "track", "artists", "album", "device", "volume", "repeat", "shuffle", "progress",
+    "duration",
+];
+const SEARCH_COLUMNS: &[&str] = &["type", "id", "name", "artists", "album"];
+
+/// matches a template's `{column}` placeholders
+static TEMPLATE_PLACEHOLDER_RE: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"\{([^}]*)\}").expect("valid placeholder regex"));
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    Table,
+    Csv,
+    /// a template with `{column}` placeholders, applied to each row
+    Template(String),
+}
+
+#[derive(clap::ValueEnum, Clone, Copy)]
+/// The `--format` argument's values
+pub enum OutputFormatKind {
+    Json,
+    Table,
+    Csv,
+    Template,
+}
+
+/// Tabular data of a CLI command's output
+pub struct Table {
+    columns: &'static [&'static str],
+    rows: Vec<Vec<String>>,
+}
+
+/// formats the data of a CLI command's output, the data's table is only built for non-JSON formats
+pub fn format_output<T: Serialize>(
+    data: &T,
+    format: &OutputFormat,
+    table: impl FnOnce(&T) -> Table,
+) -> Result<Vec<u8>> {
+    Ok(match format {
+        OutputFormat::Json => serde_json::to_vec(data)?,
+        format => table(data).render(format).into_bytes(),
+    })
+}
+
+impl Table {
+    fn new(columns: &'static [&'static str]) -> Self {
+        Self {
+            columns,
+            rows: vec![],
+        }
+    }
+
+    fn push(&mut self, row: Vec<String>) {
+        debug_assert_eq!(row.len(), self.columns.len());
+        self.rows.push(row);
+    }
+
+    pub fn tracks(tracks: &[Track]) -> Self {
+        let mut table = Self::new(TRACK_COLUMNS);
+        for track in tracks {
+            table.push(vec![
+                track.id.id().to_string(),
+                track.name.clone(),
+                track.artists_info(),
+                track.album_info(),
+                format_std_duration(track.duration),
+            ]);
+        }
+        table
+    }
+
+    pub fn episodes(episodes: &[Episode]) -> Self {
+        let mut table = Self::new(TRACK_COLUMNS);
+        for episode in episodes {
+            table.push(vec![
+                episode.id.id().to_string(),
+                episode.name.clone(),
+                String::new(),
+                episode
+                    .show
+                    .as_ref()
+                    .map(|s| s.name.clone())
+                    .unwrap_or_default(),
+                format_std_duration(episode.duration),
+            ]);
+        }
+        table
+    }
+
+    pub fn albums(albums: &[Album]) -> Self {
+        let mut table = Self::new(ALBUM_COLUMNS);
+        for album in albums {
+            table.push(vec![
+                album.id.id().to_string(),
+                album.name.clone(),
+                map_join(&album.artists, |a| &a.name, ", "),
+                album.release_date.clone(),
+            ]);
+        }
+        table
+    }
+
+    pub fn artists(artists: &[Artist]) -> Self {
+        let mut table = Self::new(ARTIST_COLUMNS);
+        for artist in artists {
+            table.push(vec![artist.id.id().to_string(), artist.name.clone()]);
+        }
+        table
+    }
+
+    pub fn playlists(playlists: &[Playlist]) -> Self {
+        let mut table = Self::new(PLAYLIST_COLUMNS);
+        for playlist in playlists {
+            table.push(vec![
+                playlist.id.id().to_string(),
+                playlist.name.clone(),
+                playlist.owner.0.clone(),
+            ]);
+        }
+        table
+    }
+
+    pub fn devices(devices: &[Device]) -> Self {
+        let mut table = Self::new(DEVICE_COLUMNS);
+        for device in devices {
+            table.push(vec![
+                device.id.clone().unwrap_or_default(),
+                device.name.clone(),
+                format!("{:?}", device._type),
+                device
+                    .volume_percent
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+                device.is_active.to_string(),
+            ]);
+        }
+        table
+    }
+
+    /// gets the table of a playback, with the same `{status}`, `{track}`, `{artists}` and `{album}`
+    /// values as the `playback_format` config option
+    pub fn playback(playback: Option<&CurrentPlaybackContext>) -> Self {
+        let mut table = Self::new(PLAYBACK_COLUMNS);
+        let Some(playback) = playback else {
+            return table;
+        };
+
+        let configs = config::get_config();
+        let status = if playback.is_playing {
+            &configs.app_config.play_icon
+        } else {
+            &configs.app_config.pause_icon
+        };
+        let [_, track, artists, album, duration] = playable_row(playback.item.as_ref());
+        table.push(vec![
+            status.clone(),
+            track,
+            artists,
+            album,
+            playback.device.name.clone(),
+            playback
+                .device
+                .volume_percent
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+            <&'static str>::from(playback.repeat_state).to_string(),
+            playback.shuffle_state.to_string(),
+            playback
+                .progress
+                .map(|p| format_duration(&p))
+                .unwrap_or_default(),
+            duration,
+        ]);
+        table
+    }
+
+    /// gets the table of the tracks and episodes in a queue, excluding the currently playing one
+    pub fn queue(queue: &CurrentUserQueue) -> Self {
+        let mut table = Self::new(TRACK_COLUMNS);
+        for item in &queue.queue {
+            table.push(playable_row(Some(item)).to_vec());
+        }
+        table
+    }
+
+    /// gets the table of a context's tracks, which are the top tracks for an artist
+    pub fn context(context: &Context) -> Self {
+        match context {
+            Context::Playlist { tracks, .. }
+            | Context::Album { tracks, .. }
+            | Context::Tracks { tracks, .. }
+            | Context::Artist {
+                top_tracks: tracks, ..
+            } => Self::tracks(tracks),
+            Context::Show { episodes, .. } => Self::episodes(episodes),
+        }
+    }
+
+    pub fn search_results(results: &SearchResults) -> Self {
+        let mut table = Self::new(SEARCH_COLUMNS);
+        for track in &results.tracks {
+            table.push(vec![
+                "track".to_string(),
+                track.id.id().to_string(),
+                track.name.clone(),
+                track.artists_info(),
+                track.album_info(),
+            ]);
+        }
+        for album in &results.albums {
+            table.push(vec![
+                "album".to_string(),
+                album.id.id().to_string(),
+                album.name.clone(),
+                map_join(&album.artists, |a| &a.name, ", "),
+                String::new(),
+            ]);
+        }
+        for artist in &results.artists {
+            table.push(vec![
+                "artist".to_string(),
+                artist.id.id().to_string(),
+                artist.name.clone(),
+                String::new(),
+                String::new(),
+            ]);
+        }
+        for playlist in &results.playlists {
+            table.push(vec![
+                "playlist".to_string(),
+                playlist.id.id().to_string(),
+                playlist.name.clone(),
+                playlist.owner.0.clone(),
+                String::new(),
+            ]);
+        }
+        for show in &results.shows {
+            table.push(vec![
+                "show".to_string(),
+                show.id.id().to_string(),
+                show.name.clone(),
+                String::new(),
+                String::new(),
+            ]);
+        }
+        for episode in &results.episodes {
+            table.push(vec![
+                "episode".to_string(),
+                episode.id.id().to_string(),
+                episode.name.clone(),
+                String::new(),
+                episode
+                    .show
+                    .as_ref()
+                    .map(|s| s.name.clone())
+                    .unwrap_or_default(),
+            ]);
+        }
+        table
+    }
+
+    /// renders the table in a non-JSON format
+    fn render(&self, format: &OutputFormat) -> String {
+        let lines: Vec<String> = match format {
+            OutputFormat::Json => unreachable!("JSON output is not rendered from a table"),
+            OutputFormat::Table => {
+                let widths: Vec<usize> = (0..self.columns.len())
+                    .map(|i| {
+                        self.rows
+                            .iter()
+                            .map(|row| row[i].chars().count())
+                            .chain([self.columns[i].len()])
+                            .max()
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                let header = self.columns.iter().map(|c| c.to_uppercase()).collect();
+                std::iter::once(&header)
+                    .chain(&self.rows)
+                    .map(|row| {
+                        row.iter()
+                            .zip(&widths)
+                            .map(|(value, width)| format!("{value:width$}"))
+                            .collect::<Vec<_>>()
+                            .join("  ")
+                            .trim_end()
+                            .to_string()
+                    })
+                    .collect()
+            }
+            OutputFormat::Csv => {
+                std::iter::once(self.columns.iter().map(|c| csv_field(c)).collect())
+                    .chain(
+                        self.rows
+                            .iter()
+                            .map(|row| row.iter().map(|v| csv_field(v)).collect()),
+                    )
+                    .map(|fields: Vec<String>| fields.join(","))
+                    .collect()
+            }
+            OutputFormat::Template(template) => self
+                .rows
+                .iter()
+                .map(|row| self.apply_template(template, row))
+                .collect(),
+        };
+        lines.join("\n")
+    }
+
+    /// applies a template to a row, replacing each `{column}` placeholder with the column's value.
+    /// Placeholders of unknown columns are replaced with empty strings.
+    fn apply_template(&self, template: &str, row: &[String]) -> String {
+        TEMPLATE_PLACEHOLDER_RE
+            .replace_all(template, |caps: &regex::Captures| {
+                let name = &caps[1];
+                self.columns
+                    .iter()
+                    .position(|c| *c == name)
+                    .map(|i| row[i].clone())
+                    .unwrap_or_default()
+            })
+            .into_owned()
+    }
+}
+
+/// gets the row of a playable item, in the order of the track columns
+fn playable_row(item: Option<&PlayableItem>) -> [String; 5] {
+    match item {
+        Some(PlayableItem::Track(track)) => [
+            track
+                .id
+                .as_ref()
+                .map(|id| id.id().to_string())
+                .unwrap_or_default(),
+            track.name.clone(),
+            map_join(&track.artists, |a| &a.name, ", "),
+            track.album.name.clone(),
+            format_duration(&track.duration),
+        ],
+        Some(PlayableItem::Episode(episode)) => [
+            episode.id.id().to_string(),
+            episode.name.clone(),
+            episode.show.publisher.clone(),
+            episode.show.name.clone(),
+            format_duration(&episode.duration),
+        ],
+        Some(PlayableItem::Unknown(_)) | None => Default::default(),
+    }
+}
+
+fn format_std_duration(duration: std::time::Duration) -> String {
+    format_duration(&chrono::Duration::from_std(duration).unwrap_or_default())
+}
+
+/// escapes a CSV field, quoting it if it contains a separator, a quote or a line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
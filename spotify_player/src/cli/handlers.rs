@@ -1,14 +1,25 @@
 use crate::{auth::AuthConfig, client};
 
 use super::{
-    config, init_cli, start_socket, AlbumId, Command, ContextType, EditAction, GetRequest,
-    IdOrName, ItemType, Key, PlaylistCommand, PlaylistId, Request, Response, TrackId,
-    MAX_REQUEST_SIZE,
+    client::handle_request,
+    config,
+    format::{OutputFormat, OutputFormatKind},
+    init_cli,
+    transport::{
+        read_frame_blocking, write_frame_blocking, RequestFrame, ResponseFrame, UdpRequest,
+    },
+    AlbumId, Command, ContextType, EditAction, GetRequest, IdOrName, ItemType, Key, LyricsCommand,
+    PlaylistCommand, PlaylistFileFormat, PlaylistId, QueueCommand, QueueItemType, Request,
+    Response, TrackId, MAX_REQUEST_SIZE,
 };
 use anyhow::{Context, Result};
 use clap::{ArgMatches, Id};
 use clap_complete::{generate, Shell};
-use std::net::UdpSocket;
+use std::{
+    io::{Read, Write},
+    net::{TcpStream, UdpSocket},
+    path::PathBuf,
+};
 
 fn receive_response(socket: &UdpSocket) -> Result<Response> {
     // read response from the server's socket, which can be split into
@@ -56,7 +67,10 @@ fn handle_get_subcommand(args: &ArgMatches) -> Request {
                 .get_one::<Key>("key")
                 .expect("key is required")
                 .to_owned();
-            Request::Get(GetRequest::Key(key))
+            Request::Get {
+                request: GetRequest::Key(key),
+                format: get_output_format(args),
+            }
         }
         "item" => {
             let item_type = args
@@ -64,7 +78,10 @@ fn handle_get_subcommand(args: &ArgMatches) -> Request {
                 .expect("context_type is required")
                 .to_owned();
             let id_or_name = get_id_or_name(args);
-            Request::Get(GetRequest::Item(item_type, id_or_name))
+            Request::Get {
+                request: GetRequest::Item(item_type, id_or_name),
+                format: get_output_format(args),
+            }
         }
         _ => unreachable!(),
     };
@@ -72,8 +89,28 @@ fn handle_get_subcommand(args: &ArgMatches) -> Request {
     request
 }
 
+fn get_output_format(args: &ArgMatches) -> OutputFormat {
+    match args
+        .get_one::<OutputFormatKind>("format")
+        .expect("format has a default value")
+    {
+        OutputFormatKind::Json => OutputFormat::Json,
+        OutputFormatKind::Table => OutputFormat::Table,
+        OutputFormatKind::Csv => OutputFormat::Csv,
+        OutputFormatKind::Template => OutputFormat::Template(
+            args.get_one::<String>("template")
+                .expect("template is required for the template format")
+                .to_owned(),
+        ),
+    }
+}
+
 fn handle_playback_subcommand(args: &ArgMatches) -> Result<Request> {
     let (cmd, args) = args.subcommand().expect("playback subcommand is required");
+    if cmd == "queue" {
+        return Ok(Request::Queue(handle_queue_subcommand(args)));
+    }
+
     let command = match cmd {
         "start" => match args.subcommand() {
             Some(("track", args)) => Command::StartTrack(get_id_or_name(args)),
@@ -139,21 +176,191 @@ fn handle_playback_subcommand(args: &ArgMatches) -> Result<Request> {
     Ok(Request::Playback(command))
 }
 
-/// Tries to connect to a running client, if exists, by sending a connection request
-/// to the client via a UDP socket.
-/// If no running client found, create a new client running in a separate thread to
-/// handle the socket request.
-fn try_connect_to_client(socket: &UdpSocket, configs: &config::Configs) -> Result<()> {
-    let port = configs.app_config.client_port;
-    socket.connect(("127.0.0.1", port))?;
+fn handle_queue_subcommand(args: &ArgMatches) -> QueueCommand {
+    let (cmd, args) = args.subcommand().expect("queue subcommand is required");
+    match cmd {
+        "add" => {
+            let item_type = args
+                .get_one::<QueueItemType>("item_type")
+                .expect("item_type is required")
+                .to_owned();
+            QueueCommand::Add(item_type, get_id_or_name(args))
+        }
+        "list" => QueueCommand::List(get_output_format(args)),
+        _ => unreachable!(),
+    }
+}
+
+/// gets the token authorizing CLI requests, either passed directly or read from a token file
+fn get_token(args: &ArgMatches) -> Result<Option<String>> {
+    if let Some(path) = args.get_one::<PathBuf>("token-file") {
+        let token = std::fs::read_to_string(path)
+            .with_context(|| format!("read the CLI token file {}", path.display()))?;
+        return Ok(Some(token.trim().to_string()));
+    }
+    Ok(args.get_one::<String>("token").cloned())
+}
+
+/// A connection to a client handling CLI requests
+enum Connection {
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixStream),
+    Tcp(TcpStream),
+    /// a UDP socket, used as a fallback for clients not listening on a stream socket
+    Udp(UdpSocket),
+    /// a new client handling the CLI request in the current process
+    Local(Box<(tokio::runtime::Runtime, client::AppClient)>),
+}
+
+impl Connection {
+    /// sends a request to the client and waits for its response
+    fn send_request(self, request: Request, token: Option<String>) -> Result<Response> {
+        match self {
+            #[cfg(unix)]
+            Self::Unix(mut stream) => send_framed_request(&mut stream, request, token),
+            Self::Tcp(mut stream) => send_framed_request(&mut stream, request, token),
+            Self::Udp(socket) => {
+                let request_buf = match token {
+                    Some(token) => serde_json::to_vec(&UdpRequest { token, request })?,
+                    None => serde_json::to_vec(&request)?,
+                };
+                anyhow::ensure!(
+                    request_buf.len() <= MAX_REQUEST_SIZE,
+                    "request of {} bytes exceeds the maximum UDP request size of {MAX_REQUEST_SIZE} bytes",
+                    request_buf.len()
+                );
+                socket.send(&request_buf)?;
+                receive_response(&socket)
+            }
+            Self::Local(local) => {
+                let (rt, client) = *local;
+                Ok(rt.block_on(handle_request(&client, None, token.as_deref(), request)))
+            }
+        }
+    }
+
+    /// subscribes to the client's playback events, printing each event as a JSON line
+    fn subscribe(self, follow: bool, token: Option<String>) -> Result<()> {
+        match self {
+            #[cfg(unix)]
+            Self::Unix(mut stream) => print_events(&mut stream, follow, token),
+            Self::Tcp(mut stream) => print_events(&mut stream, follow, token),
+            Self::Udp(_) => anyhow::bail!(
+                "The running client doesn't support event subscriptions over the UDP socket"
+            ),
+            Self::Local(_) => {
+                anyhow::bail!("Subscribing to events requires a running `spotify_player` instance")
+            }
+        }
+    }
+}
+
+/// sends a subscription request over a stream connection and prints the received events
+fn print_events<S: Read + Write>(
+    stream: &mut S,
+    follow: bool,
+    token: Option<String>,
+) -> Result<()> {
+    let id = rand::random();
+    write_frame_blocking(
+        stream,
+        &RequestFrame {
+            id,
+            token,
+            request: Request::Subscribe,
+        },
+    )
+    .context("send request")?;
+
+    let mut stdout = std::io::stdout();
+    loop {
+        // the subscription ends when the client closes the connection
+        let Some(frame) =
+            read_frame_blocking::<_, ResponseFrame>(stream).context("receive event")?
+        else {
+            return Ok(());
+        };
+        anyhow::ensure!(
+            frame.id == id,
+            "received a response to request {} instead of request {id}",
+            frame.id
+        );
+        match frame.response {
+            Response::Err(err) => anyhow::bail!("{}", String::from_utf8_lossy(&err)),
+            Response::Ok(data) => {
+                stdout.write_all(&data)?;
+                writeln!(stdout)?;
+                stdout.flush()?;
+            }
+        }
+        if !follow {
+            return Ok(());
+        }
+    }
+}
+
+/// sends a request over a stream connection as a frame tagged with a random ID
+fn send_framed_request<S: Read + Write>(
+    stream: &mut S,
+    request: Request,
+    token: Option<String>,
+) -> Result<Response> {
+    let id = rand::random();
+    write_frame_blocking(stream, &RequestFrame { id, token, request }).context("send request")?;
+
+    let frame: ResponseFrame = read_frame_blocking(stream)
+        .context("receive response")?
+        .context("connection closed before receiving a response")?;
+    anyhow::ensure!(
+        frame.id == id,
+        "received a response to request {} instead of request {id}",
+        frame.id
+    );
+    Ok(frame.response)
+}
+
+/// Connects to a running client, if exists, by trying the TCP socket (if configured),
+/// the Unix domain socket, and the UDP socket in order.
+/// If no running client found, create a new client to handle the CLI request.
+///
+/// With `stream_only`, only the stream sockets are tried, e.g. for requests that cannot be sent over
+/// the UDP socket nor handled by a new client, and an error is returned if no running client found.
+fn connect_to_client(configs: &config::Configs, stream_only: bool) -> Result<Connection> {
+    if let Some(port) = configs.app_config.client_tcp_port {
+        match TcpStream::connect(("127.0.0.1", port)) {
+            Ok(stream) => return Ok(Connection::Tcp(stream)),
+            Err(err) => tracing::debug!("Failed to connect to the client's TCP socket: {err:#}"),
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        let path = configs.client_socket_path();
+        match std::os::unix::net::UnixStream::connect(&path) {
+            Ok(stream) => return Ok(Connection::Unix(stream)),
+            Err(err) => tracing::debug!(
+                "Failed to connect to the client's Unix socket {}: {err:#}",
+                path.display()
+            ),
+        }
+    }
+
+    anyhow::ensure!(
+        !stream_only,
+        "No running `spotify_player` instance found to handle the request"
+    );
+
+    // fall back to the UDP socket used by older clients
+    let socket = UdpSocket::bind("127.0.0.1:0")?;
+    socket.connect(("127.0.0.1", configs.app_config.client_port))?;
 
     // send an empty buffer as a connection request to the client
     socket.send(&[])?;
-    if let Err(err) = socket.recv(&mut [0; 1]) {
-        if let std::io::ErrorKind::ConnectionRefused = err.kind() {
+    match socket.recv(&mut [0; 1]) {
+        Ok(_) => Ok(Connection::Udp(socket)),
+        Err(err) if err.kind() == std::io::ErrorKind::ConnectionRefused => {
             // no running `spotify_player` instance found,
             // initialize a new client to handle the current CLI command
-
             let rt = tokio::runtime::Runtime::new()?;
 
             // create a Spotify API client
@@ -163,17 +370,10 @@ fn try_connect_to_client(socket: &UdpSocket, configs: &config::Configs) -> Resul
             rt.block_on(client.new_session(None, false))
                 .context("new session")?;
 
-            // create a client socket for handling CLI commands
-            let client_socket = rt.block_on(tokio::net::UdpSocket::bind(("127.0.0.1", port)))?;
-
-            // spawn a thread to handle the CLI request
-            std::thread::spawn(move || rt.block_on(start_socket(client, client_socket, None)));
-        } else {
-            return Err(err.into());
+            Ok(Connection::Local(Box::new((rt, client))))
         }
+        Err(err) => Err(err.into()),
     }
-
-    Ok(())
 }
 
 pub fn handle_cli_subcommand(cmd: &str, args: &ArgMatches) -> Result<()> {
@@ -198,14 +398,27 @@ pub fn handle_cli_subcommand(cmd: &str, args: &ArgMatches) -> Result<()> {
         _ => {}
     }
 
-    let socket = UdpSocket::bind("127.0.0.1:0")?;
-    try_connect_to_client(&socket, configs).context("try to connect to a client")?;
+    let token = get_token(args)?;
+    anyhow::ensure!(
+        token.is_some() || configs.cli_tokens_config.tokens.is_empty(),
+        "A CLI token is required, pass it with the `--token-file` option or the `SPOTIFY_PLAYER_TOKEN` environment variable"
+    );
+
+    // subscribing to events requires a running client listening on a stream socket
+    let connection =
+        connect_to_client(configs, cmd == "events").context("try to connect to a client")?;
+
+    if cmd == "events" {
+        connection.subscribe(args.get_flag("follow"), token)?;
+        std::process::exit(0);
+    }
 
     // construct a socket request based on the CLI command and its arguments
     let request = match cmd {
         "get" => handle_get_subcommand(args),
         "playback" => handle_playback_subcommand(args)?,
         "playlist" => handle_playlist_subcommand(args)?,
+        "lyrics" => handle_lyrics_subcommand(args)?,
         "connect" => Request::Connect(get_id_or_name(args)),
         "like" => Request::Like {
             unlike: args.get_flag("unlike"),
@@ -215,17 +428,13 @@ pub fn handle_cli_subcommand(cmd: &str, args: &ArgMatches) -> Result<()> {
                 .get_one::<String>("query")
                 .expect("query is required")
                 .to_owned(),
+            format: get_output_format(args),
         },
         _ => unreachable!(),
     };
 
-    // send the request to the client's socket
-    let request_buf = serde_json::to_vec(&request)?;
-    assert!(request_buf.len() <= MAX_REQUEST_SIZE);
-    socket.send(&request_buf)?;
-
-    // receive and handle a response from the client's socket
-    match receive_response(&socket)? {
+    // send the request to the client and handle its response
+    match connection.send_request(request, token)? {
         Response::Err(err) => {
             eprintln!("{}", String::from_utf8_lossy(&err));
             std::process::exit(1);
@@ -273,16 +482,26 @@ fn handle_playlist_subcommand(args: &ArgMatches) -> Result<Request> {
         }
         "list" => PlaylistCommand::List,
         "import" => {
-            let from_s = args
-                .get_one::<String>("from")
-                .expect("'from' PlaylistID is required.")
-                .to_owned();
-
             let to_s = args
                 .get_one::<String>("to")
                 .expect("'to' PlaylistID is required.")
                 .to_owned();
 
+            if let Some(file) = args.get_one::<PathBuf>("file") {
+                // the file is read by the client, which may run in a different working directory
+                let file = std::path::absolute(file)
+                    .with_context(|| format!("get absolute path of {}", file.display()))?;
+                let to = PlaylistId::from_id(to_s.clone())?;
+
+                println!("Importing '{}' into '{to_s}'...\n", file.display());
+                return Ok(Request::Playlist(PlaylistCommand::ImportFile { file, to }));
+            }
+
+            let from_s = args
+                .get_one::<String>("from")
+                .expect("'from' PlaylistID is required.")
+                .to_owned();
+
             let delete = args.get_flag("delete");
 
             let from = PlaylistId::from_id(from_s.clone())?;
@@ -291,6 +510,25 @@ fn handle_playlist_subcommand(args: &ArgMatches) -> Result<Request> {
             println!("Importing '{from_s}' into '{to_s}'...\n");
             PlaylistCommand::Import { from, to, delete }
         }
+        "export" => {
+            let id = PlaylistId::from_id(
+                args.get_one::<String>("id")
+                    .expect("id arg is required")
+                    .to_owned(),
+            )?;
+
+            let format = *args
+                .get_one::<PlaylistFileFormat>("format")
+                .expect("format arg has a default value");
+
+            let output = args
+                .get_one::<PathBuf>("output")
+                .map(std::path::absolute)
+                .transpose()
+                .context("get absolute path of the output file")?;
+
+            PlaylistCommand::Export { id, format, output }
+        }
         "fork" => {
             let id_s = args
                 .get_one::<String>("id")
@@ -349,3 +587,34 @@ fn handle_playlist_subcommand(args: &ArgMatches) -> Result<Request> {
 
     Ok(Request::Playlist(command))
 }
+
+fn handle_lyrics_subcommand(args: &ArgMatches) -> Result<Request> {
+    let (cmd, args) = args.subcommand().expect("lyrics subcommand is required");
+    let command = match cmd {
+        "export" => {
+            let playlist_id = args
+                .get_one::<String>("playlist_id")
+                .map(|s| PlaylistId::from_id(s.to_owned()))
+                .transpose()?;
+
+            let album_id = args
+                .get_one::<String>("album_id")
+                .map(|s| AlbumId::from_id(s.to_owned()))
+                .transpose()?;
+
+            // the files are written by the client, which may run in a different working directory
+            let dir = args.get_one::<PathBuf>("dir").expect("dir arg is required");
+            let dir = std::path::absolute(dir)
+                .with_context(|| format!("get absolute path of {}", dir.display()))?;
+
+            LyricsCommand::Export {
+                playlist_id,
+                album_id,
+                dir,
+            }
+        }
+        _ => unreachable!(),
+    };
+
+    Ok(Request::Lyrics(command))
+}
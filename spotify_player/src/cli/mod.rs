@@ -1,15 +1,24 @@
 mod client;
 mod commands;
+mod format;
 mod handlers;
+mod transport;
 
-use crate::config;
+use crate::config::{self, Scope};
+use format::OutputFormat;
 use rspotify::model::{AlbumId, ArtistId, Id, PlaylistId, TrackId};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 const MAX_REQUEST_SIZE: usize = 4096;
 
-pub use client::start_socket;
+#[cfg(unix)]
+pub use client::start_unix_socket;
+pub use client::{start_socket, start_tcp_socket};
 pub use handlers::handle_cli_subcommand;
+#[cfg(unix)]
+pub use transport::bind_unix_socket;
+pub use transport::SOCKET_FILE_NAME;
 
 #[derive(Debug, Serialize, Deserialize, clap::ValueEnum, Clone)]
 pub enum Key {
@@ -30,6 +39,13 @@ pub enum ContextType {
     Artist,
 }
 
+#[derive(Debug, Serialize, Deserialize, clap::ValueEnum, Clone)]
+pub enum QueueItemType {
+    Track,
+    Album,
+    Episode,
+}
+
 #[derive(Debug, Serialize, Deserialize, clap::ValueEnum, Clone)]
 pub enum ItemType {
     Playlist,
@@ -64,6 +80,12 @@ pub enum EditAction {
     Delete,
 }
 
+#[derive(Debug, Serialize, Deserialize, clap::ValueEnum, Clone, Copy)]
+pub enum PlaylistFileFormat {
+    M3u,
+    Pls,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum PlaylistCommand {
     New {
@@ -81,6 +103,15 @@ pub enum PlaylistCommand {
         to: PlaylistId<'static>,
         delete: bool,
     },
+    ImportFile {
+        file: PathBuf,
+        to: PlaylistId<'static>,
+    },
+    Export {
+        id: PlaylistId<'static>,
+        format: PlaylistFileFormat,
+        output: Option<PathBuf>,
+    },
     Fork {
         id: PlaylistId<'static>,
     },
@@ -96,6 +127,15 @@ pub enum PlaylistCommand {
     },
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub enum LyricsCommand {
+    Export {
+        playlist_id: Option<PlaylistId<'static>>,
+        album_id: Option<AlbumId<'static>>,
+        dir: PathBuf,
+    },
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Command {
     StartContext {
@@ -123,14 +163,36 @@ pub enum Command {
     Seek(i64),
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub enum QueueCommand {
+    Add(QueueItemType, IdOrName),
+    List(OutputFormat),
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum Request {
-    Get(GetRequest),
+    Get {
+        // flattened to keep the message format of requests sent by older CLIs
+        #[serde(flatten)]
+        request: GetRequest,
+        #[serde(default)]
+        format: OutputFormat,
+    },
     Playback(Command),
     Connect(IdOrName),
-    Like { unlike: bool },
+    Like {
+        unlike: bool,
+    },
     Playlist(PlaylistCommand),
-    Search { query: String },
+    Lyrics(LyricsCommand),
+    Queue(QueueCommand),
+    Search {
+        query: String,
+        #[serde(default)]
+        format: OutputFormat,
+    },
+    /// subscribes to playback events, only supported by stream sockets
+    Subscribe,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -139,6 +201,23 @@ pub enum Response {
     Err(Vec<u8>),
 }
 
+impl Request {
+    /// gets the scope required to handle the request
+    pub fn scope(&self) -> Scope {
+        match self {
+            Self::Get { .. }
+            | Self::Search { .. }
+            | Self::Subscribe
+            | Self::Playlist(PlaylistCommand::List)
+            | Self::Queue(QueueCommand::List(_)) => Scope::Read,
+            Self::Playback(_) | Self::Connect(_) | Self::Queue(QueueCommand::Add(..)) => {
+                Scope::Playback
+            }
+            Self::Like { .. } | Self::Playlist(_) | Self::Lyrics(_) => Scope::LibraryWrite,
+        }
+    }
+}
+
 impl From<ContextType> for ItemType {
     fn from(value: ContextType) -> Self {
         match value {
@@ -149,6 +228,15 @@ impl From<ContextType> for ItemType {
     }
 }
 
+impl From<PlaylistFileFormat> for local_player::playlist::PlaylistFormat {
+    fn from(value: PlaylistFileFormat) -> Self {
+        match value {
+            PlaylistFileFormat::M3u => Self::M3u,
+            PlaylistFileFormat::Pls => Self::Pls,
+        }
+    }
+}
+
 impl ItemId {
     pub fn uri(&self) -> String {
         match self {
@@ -174,8 +262,28 @@ pub fn init_cli() -> anyhow::Result<clap::Command> {
         .subcommand(commands::init_like_command())
         .subcommand(commands::init_authenticate_command())
         .subcommand(commands::init_playlist_subcommand())
+        .subcommand(commands::init_lyrics_subcommand())
         .subcommand(commands::init_generate_command())
         .subcommand(commands::init_search_command())
+        .subcommand(commands::init_events_command())
+        .arg(
+            clap::Arg::new("token")
+                .long("token")
+                .value_name("TOKEN")
+                .env("SPOTIFY_PLAYER_TOKEN")
+                .hide_env_values(true)
+                .global(true)
+                .conflicts_with("token-file")
+                .help("Token authorizing CLI requests"),
+        )
+        .arg(
+            clap::Arg::new("token-file")
+                .long("token-file")
+                .value_name("PATH")
+                .value_parser(clap::value_parser!(PathBuf))
+                .global(true)
+                .help("File containing the token authorizing CLI requests"),
+        )
         .arg(
             clap::Arg::new("theme")
                 .short('t')
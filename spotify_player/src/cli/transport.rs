@@ -0,0 +1,337 @@
+//! Messages of CLI requests and responses, and their framing over stream sockets.
+//!
+//! Each frame is a JSON message prefixed by its length, encoded as a big-endian `u32`.
+
+use std::io::{Read, Write};
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use super::{Request, Response};
+
+/// the maximum size of a frame's message
+const MAX_FRAME_SIZE: usize = 64 * 1024 * 1024;
+
+/// the name of the client's Unix domain socket file inside the cache folder
+pub const SOCKET_FILE_NAME: &str = "client.sock";
+
+#[derive(Debug, Serialize, Deserialize)]
+/// A CLI request tagged with an ID
+pub struct RequestFrame {
+    pub id: u64,
+    #[serde(default)]
+    pub token: Option<String>,
+    pub request: Request,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+/// A CLI request with a token sent over the UDP socket.
+///
+/// Requests without a token are sent as plain `Request`s, which older clients expect.
+pub struct UdpRequest {
+    pub token: String,
+    pub request: Request,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+/// A response to the CLI request with the same ID
+pub struct ResponseFrame {
+    pub id: u64,
+    pub response: Response,
+}
+
+/// gets the length prefix of a frame's message
+fn frame_len(data: &[u8]) -> Result<[u8; 4]> {
+    anyhow::ensure!(
+        data.len() <= MAX_FRAME_SIZE,
+        "frame of {} bytes exceeds the maximum size of {MAX_FRAME_SIZE} bytes",
+        data.len()
+    );
+    Ok(u32::try_from(data.len())?.to_be_bytes())
+}
+
+/// parses the length prefix of a frame's message
+fn parse_frame_len(buf: [u8; 4]) -> Result<usize> {
+    let len = u32::from_be_bytes(buf) as usize;
+    anyhow::ensure!(
+        len <= MAX_FRAME_SIZE,
+        "frame of {len} bytes exceeds the maximum size of {MAX_FRAME_SIZE} bytes"
+    );
+    Ok(len)
+}
+
+/// reads a frame from an async stream, returns `None` if the stream is closed
+pub async fn read_frame<R, T>(reader: &mut R) -> Result<Option<T>>
+where
+    R: AsyncRead + Unpin,
+    T: DeserializeOwned,
+{
+    let mut len_buf = [0; 4];
+    match reader.read_exact(&mut len_buf).await {
+        Ok(_) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err.into()),
+    }
+
+    let mut data = vec![0; parse_frame_len(len_buf)?];
+    reader.read_exact(&mut data).await.context("read frame")?;
+    Ok(Some(
+        serde_json::from_slice(&data).context("deserialize frame")?,
+    ))
+}
+
+/// writes a frame to an async stream
+pub async fn write_frame<W, T>(writer: &mut W, frame: &T) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let data = serde_json::to_vec(frame).context("serialize frame")?;
+    writer.write_all(&frame_len(&data)?).await?;
+    writer.write_all(&data).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// reads a frame from a blocking stream, returns `None` if the stream is closed
+pub fn read_frame_blocking<R, T>(reader: &mut R) -> Result<Option<T>>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    let mut len_buf = [0; 4];
+    match reader.read_exact(&mut len_buf) {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err.into()),
+    }
+
+    let mut data = vec![0; parse_frame_len(len_buf)?];
+    reader.read_exact(&mut data).context("read frame")?;
+    Ok(Some(
+        serde_json::from_slice(&data).context("deserialize frame")?,
+    ))
+}
+
+/// writes a frame to a blocking stream
+pub fn write_frame_blocking<W, T>(writer: &mut W, frame: &T) -> Result<()>
+where
+    W: Write,
+    T: Serialize,
+{
+    let data = serde_json::to_vec(frame).context("serialize frame")?;
+    writer.write_all(&frame_len(&data)?)?;
+    writer.write_all(&data)?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(unix)]
+/// binds the client's Unix domain socket, replacing a stale socket file left by a previous instance.
+///
+/// The socket is bound inside a private folder and moved to `path` once only the current user can
+/// access it, so that other users cannot connect to it in the meantime.
+pub fn bind_unix_socket(path: &std::path::Path) -> Result<tokio::net::UnixListener> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    if path.exists() {
+        anyhow::ensure!(
+            std::os::unix::net::UnixStream::connect(path).is_err(),
+            "another client is listening on {}",
+            path.display()
+        );
+        std::fs::remove_file(path).with_context(|| format!("remove {}", path.display()))?;
+    }
+    let folder = path
+        .parent()
+        .with_context(|| format!("invalid socket path {}", path.display()))?;
+    std::fs::create_dir_all(folder).with_context(|| format!("create {}", folder.display()))?;
+
+    let private_folder = folder.join(format!(".{SOCKET_FILE_NAME}.{}", std::process::id()));
+    // a folder left by a crashed instance with the same process ID
+    std::fs::remove_dir_all(&private_folder).unwrap_or_default();
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_folder)
+        .with_context(|| format!("create {}", private_folder.display()))?;
+
+    let bind = || -> Result<tokio::net::UnixListener> {
+        let private_path = private_folder.join(SOCKET_FILE_NAME);
+        let listener = tokio::net::UnixListener::bind(&private_path)?;
+        // only the current user is allowed to send CLI requests
+        std::fs::set_permissions(&private_path, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("set permissions of {}", private_path.display()))?;
+        std::fs::rename(&private_path, path)
+            .with_context(|| format!("move the socket to {}", path.display()))?;
+        Ok(listener)
+    };
+    let result = bind();
+    std::fs::remove_dir_all(&private_folder).unwrap_or_default();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request_frame(id: u64) -> RequestFrame {
+        RequestFrame {
+            id,
+            token: Some("secret".to_string()),
+            request: Request::Like { unlike: true },
+        }
+    }
+
+    #[tokio::test]
+    async fn frames_round_trip() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        // frames larger than the stream's buffer are written and read in parts
+        let response = ResponseFrame {
+            id: 2,
+            response: Response::Ok(vec![b'x'; 1000]),
+        };
+
+        let writer = tokio::spawn(async move {
+            write_frame(&mut client, &request_frame(1)).await.unwrap();
+            write_frame(&mut client, &response).await.unwrap();
+        });
+
+        let frame: RequestFrame = read_frame(&mut server).await.unwrap().unwrap();
+        assert_eq!(frame.id, 1);
+        assert_eq!(frame.token.as_deref(), Some("secret"));
+        assert!(matches!(frame.request, Request::Like { unlike: true }));
+
+        let frame: ResponseFrame = read_frame(&mut server).await.unwrap().unwrap();
+        assert_eq!(frame.id, 2);
+        assert!(matches!(frame.response, Response::Ok(data) if data == vec![b'x'; 1000]));
+
+        writer.await.unwrap();
+        // the stream is closed after the last frame
+        assert!(read_frame::<_, RequestFrame>(&mut server)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn read_invalid_frames() {
+        // a frame exceeding the maximum size is rejected before reading its message
+        let len = u32::try_from(MAX_FRAME_SIZE + 1).unwrap();
+        let mut data = &len.to_be_bytes()[..];
+        let err = read_frame::<_, RequestFrame>(&mut data).await.unwrap_err();
+        assert!(
+            err.to_string().contains("exceeds the maximum size"),
+            "{err}"
+        );
+
+        // a stream closed in the middle of a frame
+        let mut data = &[0, 0, 0, 10, b'{'][..];
+        assert!(read_frame::<_, RequestFrame>(&mut data).await.is_err());
+        let mut data = &[0, 0][..];
+        assert!(read_frame::<_, RequestFrame>(&mut data)
+            .await
+            .unwrap()
+            .is_none());
+
+        // a frame with an invalid message
+        let mut data = &[0, 0, 0, 2, b'{', b'}'][..];
+        assert!(read_frame::<_, RequestFrame>(&mut data).await.is_err());
+    }
+
+    #[test]
+    fn blocking_frames_round_trip() {
+        let mut data = vec![];
+        write_frame_blocking(&mut data, &request_frame(1)).unwrap();
+        write_frame_blocking(&mut data, &request_frame(2)).unwrap();
+        let len = u32::from_be_bytes(data[..4].try_into().unwrap()) as usize;
+        assert_eq!(data.len(), 2 * (4 + len));
+
+        let mut reader = std::io::Cursor::new(data);
+        for id in [1, 2] {
+            let frame: RequestFrame = read_frame_blocking(&mut reader).unwrap().unwrap();
+            assert_eq!(frame.id, id);
+        }
+        assert!(read_frame_blocking::<_, RequestFrame>(&mut reader)
+            .unwrap()
+            .is_none());
+
+        let len = u32::try_from(MAX_FRAME_SIZE + 1).unwrap();
+        let mut reader = std::io::Cursor::new(len.to_be_bytes());
+        assert!(read_frame_blocking::<_, RequestFrame>(&mut reader).is_err());
+    }
+
+    #[test]
+    fn frame_len_limit() {
+        assert_eq!(frame_len(b"{}").unwrap(), [0, 0, 0, 2]);
+        assert_eq!(parse_frame_len([0, 0, 1, 0]).unwrap(), 256);
+        assert!(parse_frame_len((MAX_FRAME_SIZE as u32 + 1).to_be_bytes()).is_err());
+    }
+
+    #[test]
+    fn parse_requests_without_format() {
+        use super::super::{GetRequest, IdOrName, ItemType, Key, OutputFormat};
+
+        // requests sent by older CLIs default to the JSON output format
+        let request: Request = serde_json::from_str(r#"{"Get":{"Key":"Playback"}}"#).unwrap();
+        assert!(matches!(
+            request,
+            Request::Get {
+                request: GetRequest::Key(Key::Playback),
+                format: OutputFormat::Json,
+            }
+        ));
+        let request = Request::Get {
+            request: GetRequest::Item(ItemType::Album, IdOrName::Name("a".to_string())),
+            format: OutputFormat::Template("{name}".to_string()),
+        };
+        let request: Request =
+            serde_json::from_slice(&serde_json::to_vec(&request).unwrap()).unwrap();
+        assert!(matches!(
+            request,
+            Request::Get {
+                request: GetRequest::Item(ItemType::Album, IdOrName::Name(name)),
+                format: OutputFormat::Template(template),
+            } if name == "a" && template == "{name}"
+        ));
+
+        let request: Request = serde_json::from_str(r#"{"Search":{"query":"q"}}"#).unwrap();
+        assert!(matches!(
+            request,
+            Request::Search {
+                format: OutputFormat::Json,
+                ..
+            }
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn bind_private_unix_socket() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let folder =
+            std::env::temp_dir().join(format!("spotify_player_socket_{}", std::process::id()));
+        let path = folder.join(SOCKET_FILE_NAME);
+        // a stale socket file is replaced
+        std::fs::create_dir_all(&folder).unwrap();
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+        let listener = bind_unix_socket(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        let entries = std::fs::read_dir(&folder).unwrap().count();
+        let accepted = tokio::spawn(async move { listener.accept().await.is_ok() });
+        let connected = tokio::net::UnixStream::connect(&path).await.is_ok();
+
+        // another instance cannot bind the socket while it's in use
+        let rebind = bind_unix_socket(&path);
+        std::fs::remove_dir_all(&folder).unwrap();
+
+        assert_eq!(mode & 0o777, 0o600);
+        // the private folder is removed
+        assert_eq!(entries, 1);
+        assert!(connected);
+        assert!(accepted.await.unwrap());
+        assert!(rebind.is_err());
+    }
+}
@@ -4,11 +4,12 @@ use tracing::Instrument;
 
 use crate::{
     config,
-    state::{ContextId, ContextPageType, ContextPageUIState, PageState, PlayableId, SharedState},
+    state::{
+        ContextId, ContextPageType, ContextPageUIState, MixedEntry, MixedPlayback, PageState,
+        PlayableId, PlayerState, SharedState, TrackId,
+    },
 };
 
-use crate::utils::map_join;
-
 use super::ClientRequest;
 
 struct PlayerEventHandlerState {
@@ -109,6 +110,80 @@ fn handle_playback_change_event(
     Ok(())
 }
 
+/// starts the next segment of the mixed playback when its current segment ends
+fn handle_mixed_playback_event(
+    state: &SharedState,
+    client_pub: &flume::Sender<ClientRequest>,
+) -> anyhow::Result<()> {
+    let mut player = state.player.write();
+    let Some(last_entry) = player
+        .mixed_playback
+        .as_ref()
+        .and_then(|p| p.segment().last().cloned())
+    else {
+        return Ok(());
+    };
+
+    let ended = match last_entry {
+        MixedEntry::Local(path) => player.local_playback.as_ref().is_some_and(|p| {
+            p.track.local_path.as_ref() == Some(&path)
+                && !p.is_playing
+                && p.progress() >= p.track.duration
+        }),
+        MixedEntry::Spotify(id) => is_spotify_entry_ended(&mut player, &id),
+    };
+    if !ended {
+        return Ok(());
+    }
+
+    if player
+        .mixed_playback
+        .as_mut()
+        .is_some_and(MixedPlayback::advance)
+    {
+        client_pub.send(ClientRequest::PlayMixedSegment)?;
+    } else {
+        player.mixed_playback = None;
+    }
+    Ok(())
+}
+
+/// whether a Spotify track ending the mixed playback's segment has ended.
+///
+/// Spotify stops after the segment's last track, so the track is considered ended
+/// if its progress reaches its duration or if the playback moves away from it after it started.
+fn is_spotify_entry_ended(player: &mut PlayerState, id: &TrackId) -> bool {
+    // the local player is still playing the previous segment
+    if player.local_playback.is_some() {
+        return false;
+    }
+
+    let (is_playing, progress) = match player.current_playback() {
+        Some(playback) => (playback.is_playing, playback.progress.unwrap_or_default()),
+        None => (false, chrono::Duration::zero()),
+    };
+    let duration = match player.currently_playing() {
+        Some(rspotify::model::PlayableItem::Track(track)) if track.id.as_ref() == Some(id) => {
+            Some(track.duration)
+        }
+        _ => None,
+    };
+
+    let Some(playback) = player.mixed_playback.as_mut() else {
+        return false;
+    };
+    match duration {
+        Some(duration) => {
+            if is_playing {
+                playback.last_entry_started = true;
+            }
+            progress >= duration
+                || (playback.last_entry_started && !is_playing && progress.is_zero())
+        }
+        None => playback.last_entry_started,
+    }
+}
+
 fn handle_page_change_event(
     state: &SharedState,
     client_pub: &flume::Sender<ClientRequest>,
@@ -171,20 +246,19 @@ fn handle_page_change_event(
             track_uri,
             track,
             artists,
+            line_list,
         } => {
-            if let Some(rspotify::model::PlayableItem::Track(current_track)) =
-                state.player.read().currently_playing()
-            {
-                if current_track.name != *track {
-                    if let Some(id) = &current_track.id {
-                        tracing::info!("Currently playing track \"{}\" is different from the track \"{track}\" shown up in the lyrics page. Fetching new track's lyrics...", current_track.name);
-                        track.clone_from(&current_track.name);
-                        *artists = map_join(&current_track.artists, |a| &a.name, ", ");
-                        *track_uri = id.uri();
-                        client_pub.send(ClientRequest::GetLyrics {
-                            track_id: id.clone_static(),
-                        })?;
-                    }
+            let current_track = state.player.read().current_track();
+            if let Some(current_track) = current_track {
+                if current_track.id.uri() != *track_uri {
+                    tracing::info!("Currently playing track \"{}\" is different from the track \"{track}\" shown up in the lyrics page. Fetching new track's lyrics...", current_track.name);
+                    track.clone_from(&current_track.name);
+                    *artists = current_track.artists_info();
+                    *track_uri = current_track.id.uri();
+                    line_list.select(None);
+                    client_pub.send(ClientRequest::GetLyrics {
+                        track: current_track,
+                    })?;
                 }
             }
         }
@@ -201,6 +275,7 @@ fn handle_player_event(
 ) -> anyhow::Result<()> {
     handle_page_change_event(state, client_pub, handler_state)
         .context("handle page change event")?;
+    handle_mixed_playback_event(state, client_pub).context("handle mixed playback event")?;
     handle_playback_change_event(state, client_pub, handler_state)
         .context("handle playback change event")?;
 
@@ -7,10 +7,10 @@ use crate::{
     auth::AuthConfig,
     state::{
         store_data_into_file_cache, Album, AlbumId, Artist, ArtistId, Category, Context, ContextId,
-        Device, FileCacheKey, Item, ItemId, MemoryCaches, Playback, PlaybackMetadata, Playlist,
-        PlaylistFolderItem, PlaylistId, SearchResults, SharedState, Show, ShowId, Track, TrackId,
-        UserId, TTL_CACHE_DURATION, USER_LIKED_TRACKS_ID, USER_RECENTLY_PLAYED_TRACKS_ID,
-        USER_TOP_TRACKS_ID,
+        Device, FileCacheKey, Item, ItemId, LocalLibraryItem, MemoryCaches, MixedEntry,
+        MixedPlayback, Playback, PlaybackMetadata, Playlist, PlaylistFolderItem, PlaylistId,
+        SearchResults, SharedState, Show, ShowId, Track, TrackId, UserId, TTL_CACHE_DURATION,
+        USER_LIKED_TRACKS_ID, USER_RECENTLY_PLAYED_TRACKS_ID, USER_TOP_TRACKS_ID,
     },
 };
 
@@ -22,6 +22,7 @@ use anyhow::Result;
 #[cfg(feature = "streaming")]
 use parking_lot::Mutex;
 
+use lyric_finder::LyricProvider;
 use reqwest::StatusCode;
 use rspotify::{http::Query, prelude::*};
 
@@ -361,10 +362,10 @@ impl AppClient {
                     .category_playlists
                     .insert(category.id, playlists);
             }
-            ClientRequest::GetLyrics { track_id } => {
-                let uri = track_id.uri();
+            ClientRequest::GetLyrics { track } => {
+                let uri = track.id.uri();
                 if !state.data.read().caches.lyrics.contains_key(&uri) {
-                    let lyrics = self.lyrics(track_id).await?;
+                    let lyrics = self.lyrics(&track).await?;
                     state
                         .data
                         .write()
@@ -373,6 +374,15 @@ impl AppClient {
                         .insert(uri, lyrics, *TTL_CACHE_DURATION);
                 }
             }
+            ClientRequest::RefreshLyrics { track } => {
+                crate::lyrics::remove_cached_lyrics(&track)?;
+                let lyrics = self.lyrics(&track).await?;
+                state.data.write().caches.lyrics.insert(
+                    track.id.uri(),
+                    lyrics,
+                    *TTL_CACHE_DURATION,
+                );
+            }
             #[cfg(feature = "streaming")]
             ClientRequest::RestartIntegratedClient => {
                 self.new_session(Some(state), false).await?;
@@ -382,11 +392,62 @@ impl AppClient {
                 state.data.write().user_data.user = Some(user);
             }
             ClientRequest::Player(request) => {
+                if matches!(
+                    request,
+                    PlayerRequest::StartPlayback(..) | PlayerRequest::TransferPlayback(..)
+                ) {
+                    state.player.write().mixed_playback = None;
+                }
+                if state.player.read().local_playback.is_some() {
+                    match request {
+                        // starting a Spotify playback stops the local player
+                        PlayerRequest::StartPlayback(..) | PlayerRequest::TransferPlayback(..) => {
+                            crate::local::send_player_request(state, LocalPlayerRequest::Stop)?;
+                        }
+                        request => {
+                            return crate::local::send_player_request(
+                                state,
+                                LocalPlayerRequest::Player(request),
+                            );
+                        }
+                    }
+                }
+
                 let playback = state.player.read().buffered_playback.clone();
                 let playback = self.handle_player_request(request, playback).await?;
                 state.player.write().buffered_playback = playback;
                 self.update_playback(state);
             }
+            ClientRequest::LocalPlayer(LocalPlayerRequest::SetEqualizerPreset(name)) => {
+                crate::local::set_equalizer_preset(state, name)?;
+            }
+            ClientRequest::LocalPlayer(request) => {
+                if matches!(request, LocalPlayerRequest::StartTracks { .. }) {
+                    state.player.write().mixed_playback = None;
+                    self.pause_spotify_playback(state).await?;
+                }
+                crate::local::send_player_request(state, request)?;
+            }
+            ClientRequest::StartMixedPlayback { entries, offset } => {
+                let limit = config::get_config().app_config.tracks_playback_limit;
+                state.player.write().mixed_playback =
+                    Some(MixedPlayback::new(entries, offset, limit));
+                self.play_mixed_segment(state).await?;
+            }
+            ClientRequest::PlayMixedSegment => self.play_mixed_segment(state).await?,
+            ClientRequest::GetLocalContext(item) => {
+                let mut data = state.data.write();
+                let tracks = data.local_library.item_tracks(&item);
+                let desc = match &item {
+                    LocalLibraryItem::Album(album) => format!("Local album: {}", album.name),
+                    LocalLibraryItem::Artist(artist) => format!("Local artist: {}", artist.name),
+                };
+                data.caches.context.insert(
+                    item.context_id().uri(),
+                    Context::Tracks { tracks, desc },
+                    *TTL_CACHE_DURATION,
+                );
+            }
             ClientRequest::GetCurrentPlayback => {
                 self.retrieve_current_playback(state, true).await?;
             }
@@ -501,7 +562,7 @@ impl AppClient {
             ClientRequest::GetContext(context) => {
                 let uri = context.uri();
                 if !state.data.read().caches.context.contains_key(&uri) {
-                    let context = match context {
+                    let mut context = match context {
                         ContextId::Playlist(playlist_id) => {
                             self.playlist_context(playlist_id).await?
                         }
@@ -515,10 +576,12 @@ impl AppClient {
                         ContextId::Show(show_id) => self.show_context(show_id).await?,
                     };
 
-                    state
-                        .data
-                        .write()
-                        .caches
+                    let mut data = state.data.write();
+                    // resolve the playlist's local file entries using the local library
+                    if let Context::Playlist { tracks, .. } = &mut context {
+                        data.local_library.resolve_tracks(tracks);
+                    }
+                    data.caches
                         .context
                         .insert(uri, context, *TTL_CACHE_DURATION);
                 }
@@ -561,14 +624,7 @@ impl AppClient {
                     .await?;
             }
             ClientRequest::AddAlbumToQueue(album_id) => {
-                let album_context = self.album_context(album_id).await?;
-
-                if let Context::Album { album: _, tracks } = album_context {
-                    for track in tracks {
-                        self.add_item_to_queue(PlayableId::Track(track.id), None)
-                            .await?;
-                    }
-                }
+                self.add_album_to_queue(album_id).await?;
             }
             ClientRequest::DeleteTrackFromPlaylist(playlist_id, track_id) => {
                 self.delete_track_from_playlist(state, playlist_id, track_id)
@@ -635,20 +691,69 @@ impl AppClient {
         Ok(())
     }
 
-    /// Get lyrics of a given track, return None if no lyrics is available
-    pub async fn lyrics(&self, track_id: TrackId<'static>) -> Result<Option<Lyrics>> {
-        let session = self.session().await;
-        let id = librespot_core::spotify_id::SpotifyId::from_uri(&track_id.uri())?;
-        match librespot_metadata::Lyrics::get(&session, &id).await {
-            Ok(lyrics) => Ok(Some(lyrics.into())),
-            Err(err) => {
-                if err.to_string().to_lowercase().contains("not found") {
-                    Ok(None)
-                } else {
-                    Err(err.into())
+    /// Get lyrics of a given track along with their translation, return None if no lyrics is available.
+    pub async fn lyrics(&self, track: &Track) -> Result<Option<Lyrics>> {
+        let mut lyrics = self.original_lyrics(track).await?;
+        if let Some(lyrics) = &mut lyrics {
+            crate::lyrics::add_translation(track, lyrics);
+        }
+        Ok(lyrics)
+    }
+
+    /// Get lyrics of a given track, return None if no lyrics is available.
+    ///
+    /// Lyrics are read from the local lyrics files if any, otherwise they are read from
+    /// the on-disk lyrics cache or fetched and stored into the cache.
+    async fn original_lyrics(&self, track: &Track) -> Result<Option<Lyrics>> {
+        // local lyrics files take priority over the fetched lyrics
+        if let Some(lyrics) = crate::lyrics::local_lyrics(track) {
+            return Ok(Some(lyrics));
+        }
+
+        crate::lyrics::cached_or_fetch(track, Box::pin(self.fetch_lyrics(track)))
+            .await
+            .with_context(|| format!("get lyrics of \"{}\"", track.name))
+    }
+
+    /// Fetch lyrics of a given track from Spotify, falling back to the configured lyrics providers
+    /// if Spotify has no lyrics for the track.
+    async fn fetch_lyrics(&self, track: &Track) -> Result<Option<Lyrics>> {
+        // local tracks have no Spotify lyrics
+        if !track.is_local {
+            let session = self.session().await;
+            let id = librespot_core::spotify_id::SpotifyId::from_uri(&track.id.uri())?;
+            match librespot_metadata::Lyrics::get(&session, &id).await {
+                Ok(lyrics) => return Ok(Some(lyrics.into())),
+                Err(err) => {
+                    if !err.to_string().to_lowercase().contains("not found") {
+                        return Err(err.into());
+                    }
                 }
             }
         }
+
+        self.provider_lyrics(track).await
+    }
+
+    /// Get lyrics of a given track from the configured lyrics providers
+    async fn provider_lyrics(&self, track: &Track) -> Result<Option<Lyrics>> {
+        let query = lyric_finder::Query {
+            track: track.name.clone(),
+            artists: track.artists.iter().map(|a| a.name.clone()).collect(),
+            duration: Some(track.duration),
+        };
+        let providers = config::get_config()
+            .app_config
+            .lyrics
+            .provider_chain(&self.http);
+        match providers.find_lyric(&query).await? {
+            lyric_finder::LyricResult::Some { lyric, source, .. } => {
+                // synced lyrics are in the LRC format, and unsynced lyrics are parsed as plain text
+                let lrc = lyric_finder::lrc::Lrc::parse(&lyric);
+                Ok(Some(Lyrics::from_lrc(lrc, &source)))
+            }
+            lyric_finder::LyricResult::None => Ok(None),
+        }
     }
 
     /// Get user available devices
@@ -1084,6 +1189,82 @@ impl AppClient {
             .await?)
     }
 
+    /// pauses the Spotify playback if it's playing, e.g. before playing local tracks
+    async fn pause_spotify_playback(&self, state: &SharedState) -> Result<()> {
+        let playback = state.player.read().buffered_playback.clone();
+        if playback.as_ref().is_some_and(|p| p.is_playing) {
+            let playback = self
+                .handle_player_request(PlayerRequest::Pause, playback)
+                .await?;
+            state.player.write().buffered_playback = playback;
+            self.update_playback(state);
+        }
+        Ok(())
+    }
+
+    /// plays the current segment of the mixed playback with either Spotify or the local player
+    async fn play_mixed_segment(&self, state: &SharedState) -> Result<()> {
+        let Some(segment) = state
+            .player
+            .read()
+            .mixed_playback
+            .as_ref()
+            .map(|p| p.segment().to_vec())
+        else {
+            return Ok(());
+        };
+
+        let mut paths = vec![];
+        let mut ids = vec![];
+        for entry in segment {
+            match entry {
+                MixedEntry::Local(path) => paths.push(path),
+                MixedEntry::Spotify(id) => ids.push(id.into()),
+            }
+        }
+
+        if !paths.is_empty() {
+            self.pause_spotify_playback(state).await?;
+            crate::local::send_player_request(
+                state,
+                LocalPlayerRequest::StartTracks {
+                    tracks: paths,
+                    offset: 0,
+                },
+            )
+        } else if !ids.is_empty() {
+            // starting a Spotify playback stops the local player
+            if state.player.read().local_playback.is_some() {
+                crate::local::send_player_request(state, LocalPlayerRequest::Stop)?;
+            }
+            let playback = state.player.read().buffered_playback.clone();
+            let playback = self
+                .handle_player_request(
+                    PlayerRequest::StartPlayback(Playback::URIs(ids, None), None),
+                    playback,
+                )
+                .await?;
+            state.player.write().buffered_playback = playback;
+            self.update_playback(state);
+            Ok(())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Add the tracks of an album to the queue
+    pub async fn add_album_to_queue(&self, album_id: AlbumId<'_>) -> Result<()> {
+        let album_context = self.album_context(album_id).await?;
+
+        if let Context::Album { album: _, tracks } = album_context {
+            for track in tracks {
+                self.add_item_to_queue(PlayableId::Track(track.id), None)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
     /// Add a playable item to a playlist
     pub async fn add_item_to_playlist(
         &self,
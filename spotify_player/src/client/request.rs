@@ -1,5 +1,8 @@
+use std::path::PathBuf;
+
 use crate::state::{
-    AlbumId, Category, ContextId, Item, ItemId, PlayableId, Playback, PlaylistId, TrackId,
+    AlbumId, Category, ContextId, Item, ItemId, LocalLibraryItem, MixedEntry, PlayableId, Playback,
+    PlaylistId, Track, TrackId,
 };
 
 #[derive(Clone, Debug)]
@@ -19,6 +22,21 @@ pub enum PlayerRequest {
     StartPlayback(Playback, Option<bool>),
 }
 
+#[derive(Clone, Debug)]
+/// A request to the local player, which plays audio files from the local library
+pub enum LocalPlayerRequest {
+    StartTracks {
+        tracks: Vec<PathBuf>,
+        offset: usize,
+    },
+    AddToQueue(PathBuf),
+    /// a playback request forwarded to the local player while it is playing
+    Player(PlayerRequest),
+    Stop,
+    /// switches the equalizer preset, applied to the local player when it is running
+    SetEqualizerPreset(String),
+}
+
 #[derive(Clone, Debug)]
 /// A request to the client
 pub enum ClientRequest {
@@ -54,9 +72,21 @@ pub enum ClientRequest {
     AddToLibrary(Item),
     DeleteFromLibrary(ItemId),
     Player(PlayerRequest),
+    LocalPlayer(LocalPlayerRequest),
+    /// starts a playback of a context mixing Spotify tracks and local tracks
+    StartMixedPlayback {
+        entries: Vec<MixedEntry>,
+        offset: usize,
+    },
+    /// plays the current segment of the mixed playback
+    PlayMixedSegment,
+    GetLocalContext(LocalLibraryItem),
     GetCurrentUserQueue,
     GetLyrics {
-        track_id: TrackId<'static>,
+        track: Track,
+    },
+    RefreshLyrics {
+        track: Track,
     },
     #[cfg(feature = "streaming")]
     RestartIntegratedClient,
@@ -51,6 +51,7 @@ pub enum Command {
 
     SwitchTheme,
     SwitchDevice,
+    SwitchEqualizerPreset,
     Search,
     Queue,
 
@@ -68,6 +69,10 @@ pub enum Command {
     RecentlyPlayedTrackPage,
     LikedTrackPage,
     LyricsPage,
+    RefreshLyrics,
+    IncreaseLyricsOffset,
+    DecreaseLyricsOffset,
+    ToggleLyricsTranslation,
     LibraryPage,
     SearchPage,
     BrowsePage,
@@ -340,6 +345,9 @@ impl Command {
             Self::FocusPreviousWindow => "focus the previous focusable window (if any)",
             Self::SwitchTheme => "open a popup for switching theme",
             Self::SwitchDevice => "open a popup for switching device",
+            Self::SwitchEqualizerPreset => {
+                "open a popup for switching the local player's equalizer preset"
+            }
             Self::Search => "open a popup for searching in the current page",
             Self::BrowseUserPlaylists => "open a popup for browsing user's playlists",
             Self::BrowseUserFollowedArtists => "open a popup for browsing user's followed artists",
@@ -349,6 +357,12 @@ impl Command {
             Self::RecentlyPlayedTrackPage => "go to the user recently played track page",
             Self::LikedTrackPage => "go to the user liked track page",
             Self::LyricsPage => "go to the lyrics page of the current track",
+            Self::IncreaseLyricsOffset => "show the lyrics of the current track 100ms sooner",
+            Self::DecreaseLyricsOffset => "show the lyrics of the current track 100ms later",
+            Self::ToggleLyricsTranslation => {
+                "toggle between the original lyrics, their translation and both"
+            }
+            Self::RefreshLyrics => "refetch the lyrics of the current track, ignoring the cached lyrics",
             Self::LibraryPage => "go to the user library page",
             Self::SearchPage => "go to the search page",
             Self::BrowsePage => "go to the browse page",
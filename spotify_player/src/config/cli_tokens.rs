@@ -0,0 +1,83 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+/// A permission granted to a CLI token
+pub enum Scope {
+    /// getting data, searching and subscribing to events
+    Read,
+    /// controlling the playback and connecting to devices
+    Playback,
+    /// modifying the user's library and playlists, and exporting files
+    LibraryWrite,
+}
+
+impl std::fmt::Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scope = match self {
+            Self::Read => "read",
+            Self::Playback => "playback",
+            Self::LibraryWrite => "library-write",
+        };
+        write!(f, "{scope}")
+    }
+}
+
+#[derive(Debug, Deserialize)]
+/// A shared-secret token authorizing CLI requests
+pub struct CliToken {
+    pub name: String,
+    pub token: String,
+    pub scopes: Vec<Scope>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+/// CLI tokens configurations, CLI requests don't require a token if no token is configured
+pub struct CliTokensConfig {
+    #[serde(default)]
+    pub tokens: Vec<CliToken>,
+}
+
+impl CliTokensConfig {
+    pub fn new(path: &std::path::Path) -> Result<Self> {
+        let file_path = path.join(super::CLI_TOKENS_CONFIG_FILE);
+        let content = match std::fs::read_to_string(&file_path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            if std::fs::metadata(&file_path)?.permissions().mode() & 0o077 != 0 {
+                tracing::warn!(
+                    "The CLI tokens config file {} is accessible by other users",
+                    file_path.display()
+                );
+            }
+        }
+
+        let config: Self = toml::from_str(&content)
+            .with_context(|| format!("parse the CLI tokens config file {}", file_path.display()))?;
+        anyhow::ensure!(
+            config.tokens.iter().all(|t| !t.token.is_empty()),
+            "CLI tokens must not be empty"
+        );
+        Ok(config)
+    }
+
+    /// finds a configured token matching the given secret
+    pub fn find(&self, token: &str) -> Option<&CliToken> {
+        self.tokens
+            .iter()
+            .find(|t| constant_time_eq(t.token.as_bytes(), token.as_bytes()))
+    }
+}
+
+/// compares two byte strings in a time independent of their content
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
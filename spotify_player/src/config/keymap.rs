@@ -144,6 +144,10 @@ impl Default for KeymapConfig {
                     key_sequence: "D".into(),
                     command: Command::SwitchDevice,
                 },
+                Keymap {
+                    key_sequence: "E".into(),
+                    command: Command::SwitchEqualizerPreset,
+                },
                 Keymap {
                     key_sequence: "u p".into(),
                     command: Command::BrowseUserPlaylists,
@@ -180,6 +184,22 @@ impl Default for KeymapConfig {
                     key_sequence: "l".into(),
                     command: Command::LyricsPage,
                 },
+                Keymap {
+                    key_sequence: "L".into(),
+                    command: Command::RefreshLyrics,
+                },
+                Keymap {
+                    key_sequence: "}".into(),
+                    command: Command::IncreaseLyricsOffset,
+                },
+                Keymap {
+                    key_sequence: "{".into(),
+                    command: Command::DecreaseLyricsOffset,
+                },
+                Keymap {
+                    key_sequence: "t".into(),
+                    command: Command::ToggleLyricsTranslation,
+                },
                 Keymap {
                     key_sequence: "g l".into(),
                     command: Command::LibraryPage,
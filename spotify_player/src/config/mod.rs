@@ -1,3 +1,4 @@
+mod cli_tokens;
 mod keymap;
 mod theme;
 
@@ -6,8 +7,9 @@ const DEFAULT_CACHE_FOLDER: &str = ".cache/spotify-player";
 const APP_CONFIG_FILE: &str = "app.toml";
 const THEME_CONFIG_FILE: &str = "theme.toml";
 const KEYMAP_CONFIG_FILE: &str = "keymap.toml";
+const CLI_TOKENS_CONFIG_FILE: &str = "cli_tokens.toml";
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use config_parser2::{config_parser_impl, ConfigParse, ConfigParser};
 use librespot_core::config::SessionConfig;
 use reqwest::Url;
@@ -17,9 +19,11 @@ use std::{
     sync::OnceLock,
 };
 
+use cli_tokens::CliTokensConfig;
 use keymap::KeymapConfig;
 use theme::ThemeConfig;
 
+pub use cli_tokens::Scope;
 pub use theme::Theme;
 
 use crate::auth::SPOTIFY_CLIENT_ID;
@@ -31,6 +35,7 @@ pub struct Configs {
     pub app_config: AppConfig,
     pub keymap_config: KeymapConfig,
     pub theme_config: ThemeConfig,
+    pub cli_tokens_config: CliTokensConfig,
     pub cache_folder: std::path::PathBuf,
 }
 
@@ -40,9 +45,18 @@ impl Configs {
             app_config: AppConfig::new(config_folder)?,
             keymap_config: KeymapConfig::new(config_folder)?,
             theme_config: ThemeConfig::new(config_folder)?,
+            cli_tokens_config: CliTokensConfig::new(config_folder)?,
             cache_folder: cache_folder.to_path_buf(),
         })
     }
+
+    /// gets the path of the client's Unix domain socket for handling CLI commands
+    pub fn client_socket_path(&self) -> PathBuf {
+        self.app_config
+            .client_socket
+            .clone()
+            .unwrap_or_else(|| self.cache_folder.join(crate::cli::SOCKET_FILE_NAME))
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, ConfigParse)]
@@ -54,6 +68,8 @@ pub struct AppConfig {
     pub client_id_command: Option<Command>,
 
     pub client_port: u16,
+    pub client_socket: Option<PathBuf>,
+    pub client_tcp_port: Option<u16>,
 
     pub login_redirect_uri: String,
 
@@ -84,6 +100,7 @@ pub struct AppConfig {
     pub play_icon: String,
     pub pause_icon: String,
     pub liked_icon: String,
+    pub local_icon: String,
 
     // layout configs
     pub border_type: BorderType,
@@ -117,6 +134,12 @@ pub struct AppConfig {
 
     pub device: DeviceConfig,
 
+    pub local_player: LocalPlayerConfig,
+
+    pub local_library: LocalLibraryConfig,
+
+    pub lyrics: LyricsConfig,
+
     #[cfg(all(feature = "streaming", feature = "notify"))]
     pub notify_streaming_only: bool,
 
@@ -195,6 +218,67 @@ pub struct DeviceConfig {
     pub autoplay: bool,
 }
 
+#[derive(Debug, Deserialize, Serialize, ConfigParse, Clone)]
+/// Application local player configurations
+pub struct LocalPlayerConfig {
+    pub output_backend: OutputBackend,
+    pub output_file: Option<PathBuf>,
+    pub gapless: bool,
+    pub crossfade_duration_secs: u16,
+    pub normalization: bool,
+    pub normalization_mode: NormalizationMode,
+    pub normalization_fallback_gain_db: f32,
+    pub equalizer_preset: String,
+    pub equalizer_presets: Vec<EqualizerPreset>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+pub enum OutputBackend {
+    Default,
+    Wav,
+    Null,
+}
+config_parser_impl!(OutputBackend);
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+pub enum NormalizationMode {
+    Track,
+    Album,
+}
+config_parser_impl!(NormalizationMode);
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+/// A named set of equalizer bands
+pub struct EqualizerPreset {
+    pub name: String,
+    #[serde(default)]
+    pub bands: Vec<local_player::equalizer::Band>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ConfigParse, Clone)]
+/// Application local music library configurations
+pub struct LocalLibraryConfig {
+    pub music_dirs: Vec<PathBuf>,
+    pub watch: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, ConfigParse, Clone)]
+/// Application lyrics configurations
+pub struct LyricsConfig {
+    pub providers: Vec<LyricsProvider>,
+    pub genius_base_url: String,
+    pub lrclib_base_url: String,
+    pub folder: Option<PathBuf>,
+    pub translation_language: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum LyricsProvider {
+    Genius,
+    LrcLib,
+}
+config_parser_impl!(LyricsProvider);
+
 #[derive(Debug, Deserialize, Serialize, ConfigParse, Clone)]
 #[cfg(feature = "notify")]
 pub struct NotifyFormat {
@@ -275,6 +359,8 @@ impl Default for AppConfig {
             client_id_command: None,
 
             client_port: 8080,
+            client_socket: None,
+            client_tcp_port: None,
 
             login_redirect_uri: "http://127.0.0.1:8989/login".to_string(),
 
@@ -311,6 +397,7 @@ impl Default for AppConfig {
             pause_icon: "▌▌".to_string(),
             play_icon: "▶".to_string(),
             liked_icon: "♥".to_string(),
+            local_icon: "L".to_string(),
 
             border_type: BorderType::Plain,
             progress_bar_type: ProgressBarType::Rectangle,
@@ -351,6 +438,12 @@ impl Default for AppConfig {
 
             device: DeviceConfig::default(),
 
+            local_player: LocalPlayerConfig::default(),
+
+            local_library: LocalLibraryConfig::default(),
+
+            lyrics: LyricsConfig::default(),
+
             #[cfg(all(feature = "streaming", feature = "notify"))]
             notify_streaming_only: false,
 
@@ -375,6 +468,150 @@ impl Default for DeviceConfig {
     }
 }
 
+impl LocalPlayerConfig {
+    /// gets the audio output of the local player
+    pub fn output(&self) -> Result<local_player::output::AudioOutput> {
+        Ok(match self.output_backend {
+            OutputBackend::Default => local_player::output::AudioOutput::Default,
+            OutputBackend::Wav => {
+                let path = self
+                    .output_file
+                    .clone()
+                    .context("`output_file` must be set for the `Wav` output backend")?;
+                local_player::output::AudioOutput::Wav(path)
+            }
+            OutputBackend::Null => local_player::output::AudioOutput::Null,
+        })
+    }
+
+    /// gets the loudness normalization settings of the local player, if enabled
+    pub fn normalization(&self) -> Option<local_player::replay_gain::Normalization> {
+        self.normalization
+            .then_some(local_player::replay_gain::Normalization {
+                mode: match self.normalization_mode {
+                    NormalizationMode::Track => local_player::replay_gain::GainMode::Track,
+                    NormalizationMode::Album => local_player::replay_gain::GainMode::Album,
+                },
+                fallback_gain: self.normalization_fallback_gain_db,
+            })
+    }
+
+    /// gets the available equalizer presets, which are the user-defined presets followed by
+    /// the application's default presets whose names don't conflict with the user-defined ones
+    pub fn equalizer_presets(&self) -> Vec<EqualizerPreset> {
+        let mut presets = self.equalizer_presets.clone();
+        for preset in default_equalizer_presets() {
+            if !presets.iter().any(|p| p.name == preset.name) {
+                presets.push(preset);
+            }
+        }
+        presets
+    }
+
+    /// finds an equalizer preset by its name
+    pub fn find_equalizer_preset(&self, name: &str) -> Option<EqualizerPreset> {
+        self.equalizer_presets()
+            .into_iter()
+            .find(|p| p.name == name)
+    }
+}
+
+impl Default for LocalPlayerConfig {
+    fn default() -> Self {
+        Self {
+            output_backend: OutputBackend::Default,
+            output_file: None,
+            gapless: true,
+            crossfade_duration_secs: 0,
+            normalization: false,
+            normalization_mode: NormalizationMode::Track,
+            normalization_fallback_gain_db: -6.0,
+            equalizer_preset: "Flat".to_string(),
+            equalizer_presets: vec![],
+        }
+    }
+}
+
+fn default_equalizer_presets() -> Vec<EqualizerPreset> {
+    use local_player::equalizer::{Band, BandType};
+
+    let band = |typ, frequency, gain| Band {
+        typ,
+        frequency,
+        gain,
+        q: std::f32::consts::FRAC_1_SQRT_2,
+    };
+    let preset = |name: &str, bands| EqualizerPreset {
+        name: name.to_string(),
+        bands,
+    };
+
+    vec![
+        preset("Flat", vec![]),
+        preset("Bass Boost", vec![band(BandType::LowShelf, 100.0, 6.0)]),
+        preset("Treble Boost", vec![band(BandType::HighShelf, 8000.0, 4.0)]),
+        preset(
+            "V-Shape",
+            vec![
+                band(BandType::LowShelf, 120.0, 5.0),
+                band(BandType::Peaking, 1000.0, -3.0),
+                band(BandType::HighShelf, 8000.0, 4.0),
+            ],
+        ),
+        preset(
+            "Vocal",
+            vec![
+                band(BandType::LowShelf, 150.0, -2.0),
+                band(BandType::Peaking, 2500.0, 3.0),
+                band(BandType::HighShelf, 10000.0, -1.0),
+            ],
+        ),
+    ]
+}
+
+impl Default for LocalLibraryConfig {
+    fn default() -> Self {
+        Self {
+            music_dirs: vec![],
+            watch: true,
+        }
+    }
+}
+
+impl LyricsConfig {
+    /// gets the chain of lyrics providers used when Spotify has no lyrics for a track
+    pub fn provider_chain(&self, http: &reqwest::Client) -> lyric_finder::ProviderChain {
+        lyric_finder::ProviderChain::new(
+            self.providers
+                .iter()
+                .map(|provider| -> Box<dyn lyric_finder::LyricProvider> {
+                    match provider {
+                        LyricsProvider::Genius => Box::new(lyric_finder::Client::with_base_url(
+                            http,
+                            &self.genius_base_url,
+                        )),
+                        LyricsProvider::LrcLib => Box::new(
+                            lyric_finder::LrcLibClient::with_base_url(http, &self.lrclib_base_url),
+                        ),
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+impl Default for LyricsConfig {
+    fn default() -> Self {
+        Self {
+            providers: vec![LyricsProvider::LrcLib, LyricsProvider::Genius],
+            genius_base_url: "https://genius.com".to_string(),
+            lrclib_base_url: "https://lrclib.net".to_string(),
+            folder: None,
+            translation_language: "en".to_string(),
+        }
+    }
+}
+
 impl Default for LayoutConfig {
     fn default() -> Self {
         Self {
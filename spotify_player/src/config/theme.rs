@@ -81,6 +81,7 @@ struct ComponentStyle {
     like: Option<Style>,
     lyrics_played: Option<Style>,
     lyrics_playing: Option<Style>,
+    lyrics_playing_word: Option<Style>,
 }
 
 #[derive(Default, Clone, Debug, Deserialize)]
@@ -373,6 +374,18 @@ impl Theme {
             )
             .style(&self.palette)
     }
+
+    pub fn lyrics_playing_word(&self) -> style::Style {
+        self.component_style
+            .lyrics_playing_word
+            .as_ref()
+            .unwrap_or(
+                &Style::default()
+                    .fg(StyleColor::Cyan)
+                    .modifiers([StyleModifier::Bold]),
+            )
+            .style(&self.palette)
+    }
 }
 
 impl Style {
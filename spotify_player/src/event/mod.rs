@@ -1,5 +1,5 @@
 use crate::{
-    client::{ClientRequest, PlayerRequest},
+    client::{ClientRequest, LocalPlayerRequest, PlayerRequest},
     command::{
         self, construct_artist_actions, Action, ActionContext, ActionTarget, Command,
         CommandOrAction,
@@ -7,19 +7,19 @@ use crate::{
     config,
     key::{Key, KeySequence},
     state::{
-        ActionListItem, Album, AlbumId, Artist, ArtistFocusState, ArtistId, ArtistPopupAction,
-        BrowsePageUIState, Context, ContextId, ContextPageType, ContextPageUIState, DataReadGuard,
-        Focusable, Id, Item, ItemId, LibraryFocusState, LibraryPageUIState, PageState, PageType,
-        PlayableId, Playback, PlaylistCreateCurrentField, PlaylistFolderItem, PlaylistId,
-        PlaylistPopupAction, PopupState, SearchFocusState, SearchPageUIState, SharedState, ShowId,
-        Track, TrackId, TrackOrder, UIStateGuard, USER_LIKED_TRACKS_ID,
+        store_data_into_file_cache, ActionListItem, Album, AlbumId, Artist, ArtistFocusState,
+        ArtistId, ArtistPopupAction, BrowsePageUIState, Context, ContextId, ContextPageType,
+        ContextPageUIState, DataReadGuard, FileCacheKey, Focusable, Id, Item, ItemId,
+        LibraryFocusState, LibraryPageUIState, PageState, PageType, PlayableId, Playback,
+        PlaylistCreateCurrentField, PlaylistFolderItem, PlaylistId, PlaylistPopupAction,
+        PopupState, SearchFocusState, SearchPageUIState, SharedState, ShowId, Track, TrackId,
+        TrackOrder, UIStateGuard, LYRICS_OFFSET_STEP_MS, USER_LIKED_TRACKS_ID,
         USER_RECENTLY_PLAYED_TRACKS_ID, USER_TOP_TRACKS_ID,
     },
     ui::{single_line_input::LineInput, Orientation},
     utils::parse_uri,
 };
 
-use crate::utils::map_join;
 use anyhow::{Context as _, Result};
 use crossterm::event::KeyCode;
 
@@ -176,6 +176,8 @@ pub fn handle_action_in_context(
     ui: &mut UIStateGuard,
 ) -> Result<bool> {
     match context {
+        // local tracks don't exist on Spotify, so no action can be done on them
+        ActionContext::Track(track) if track.is_local => Ok(false),
         ActionContext::Track(track) => match action {
             Action::GoToAlbum => {
                 if let Some(album) = track.album {
@@ -770,27 +772,55 @@ fn handle_global_command(
             }
         }
         Command::LyricsPage => {
-            if let Some(rspotify::model::PlayableItem::Track(track)) =
-                state.player.read().currently_playing()
-            {
-                if let Some(id) = &track.id {
-                    let artists = map_join(&track.artists, |a| &a.name, ", ");
-                    ui.new_page(PageState::Lyrics {
-                        track_uri: id.uri(),
-                        track: track.name.clone(),
-                        artists,
-                    });
+            let track = state.player.read().current_track();
+            if let Some(track) = track {
+                ui.new_page(PageState::Lyrics {
+                    track_uri: track.id.uri(),
+                    track: track.name.clone(),
+                    artists: track.artists_info(),
+                    line_list: ListState::default(),
+                });
 
-                    client_pub.send(ClientRequest::GetLyrics {
-                        track_id: id.clone_static(),
-                    })?;
-                }
+                client_pub.send(ClientRequest::GetLyrics { track })?;
+            }
+        }
+        Command::RefreshLyrics => {
+            let track = state.player.read().current_track();
+            if let Some(track) = track {
+                // remove the in-memory lyrics to show the lyrics page as loading
+                state.data.write().caches.lyrics.remove(&track.id.uri());
+                client_pub.send(ClientRequest::RefreshLyrics { track })?;
             }
         }
+        Command::IncreaseLyricsOffset => {
+            shift_lyrics_offset(state, LYRICS_OFFSET_STEP_MS)?;
+        }
+        Command::DecreaseLyricsOffset => {
+            shift_lyrics_offset(state, -LYRICS_OFFSET_STEP_MS)?;
+        }
+        Command::ToggleLyricsTranslation => {
+            ui.lyrics_translation_mode = ui.lyrics_translation_mode.next();
+        }
         Command::SwitchDevice => {
             ui.popup = Some(PopupState::DeviceList(ListState::default()));
             client_pub.send(ClientRequest::GetDevices)?;
         }
+        Command::SwitchEqualizerPreset => {
+            let presets: Vec<_> = config::get_config()
+                .app_config
+                .local_player
+                .equalizer_presets()
+                .into_iter()
+                .map(|p| p.name)
+                .collect();
+            let current_preset = &state.player.read().equalizer_preset;
+            let id = presets.iter().position(|name| name == current_preset);
+
+            ui.popup = Some(PopupState::EqualizerPresetList(
+                presets,
+                ListState::default().with_selected(id),
+            ));
+        }
         Command::SwitchTheme => {
             // get the available themes with the current theme moved to the first position
             let mut themes = config::get_config().theme_config.themes.clone();
@@ -861,3 +891,20 @@ fn handle_global_command(
     }
     Ok(true)
 }
+
+/// Shift the lyrics offset of the current track by `delta` milliseconds
+fn shift_lyrics_offset(state: &SharedState, delta: i64) -> Result<()> {
+    let Some(track) = state.player.read().current_track() else {
+        return Ok(());
+    };
+
+    let mut data = state.data.write();
+    data.shift_lyrics_offset(&track.id.uri(), delta);
+    store_data_into_file_cache(
+        FileCacheKey::LyricsOffsets,
+        &config::get_config().cache_folder,
+        &data.lyrics_offsets,
+    )
+    .context("store lyrics offsets")?;
+    Ok(())
+}
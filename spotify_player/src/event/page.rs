@@ -25,8 +25,7 @@ pub fn handle_key_sequence_for_page(
             PageType::Library => handle_command_for_library_page(command, client_pub, ui, state),
             PageType::Context => handle_command_for_context_page(command, client_pub, ui, state),
             PageType::Browse => handle_command_for_browse_page(command, client_pub, ui, state),
-            // lyrics page doesn't support any commands
-            PageType::Lyrics => Ok(false),
+            PageType::Lyrics => handle_command_for_lyrics_page(command, client_pub, ui, state),
             PageType::Queue => Ok(handle_command_for_queue_page(command, ui)),
             PageType::CommandHelp => Ok(handle_command_for_command_help_page(command, ui)),
         },
@@ -79,6 +78,8 @@ fn handle_action_for_library_page(
             ui,
             client_pub,
         ),
+        // local library items don't support any actions
+        LibraryFocusState::LocalFiles => Ok(false),
     }
 }
 
@@ -118,12 +119,12 @@ fn handle_command_for_library_page(
         // Sort albums alphabetically
         data.user_data
             .saved_albums
-            .sort_by(|x, y| x.name.to_lowercase().cmp(&y.name.to_lowercase()));
+            .sort_by_key(|x| x.name.to_lowercase());
 
         // Sort artists alphabetically
         data.user_data
             .followed_artists
-            .sort_by(|x, y| x.name.to_lowercase().cmp(&y.name.to_lowercase()));
+            .sort_by_key(|x| x.name.to_lowercase());
     }
 
     if command == Command::SortLibraryByRecent {
@@ -152,7 +153,7 @@ fn handle_command_for_library_page(
         // Sort albums by recent addition
         data.user_data
             .saved_albums
-            .sort_by(|a, b| b.added_at.cmp(&a.added_at));
+            .sort_by_key(|a| std::cmp::Reverse(a.added_at));
     }
 
     match focus_state {
@@ -189,6 +190,15 @@ fn handle_command_for_library_page(
                 ui,
             ))
         }
+        LibraryFocusState::LocalFiles => {
+            let data = state.data.read();
+            window::handle_command_for_local_item_list_window(
+                command,
+                &ui.search_filtered_items(&data.local_library.items),
+                ui,
+                client_pub,
+            )
+        }
     }
 }
 
@@ -481,6 +491,79 @@ fn handle_command_for_browse_page(
     Ok(true)
 }
 
+fn handle_command_for_lyrics_page(
+    command: Command,
+    client_pub: &flume::Sender<ClientRequest>,
+    ui: &mut UIStateGuard,
+    state: &SharedState,
+) -> Result<bool> {
+    let data = state.data.read();
+    let track_uri = match ui.current_page() {
+        PageState::Lyrics { track_uri, .. } => track_uri.clone(),
+        _ => anyhow::bail!("expect a lyrics page state"),
+    };
+    // only synced lyrics can be searched and seeked through
+    let Some(Some(lyrics)) = data.caches.lyrics.get(&track_uri) else {
+        return Ok(false);
+    };
+    if !lyrics.synced {
+        return Ok(false);
+    }
+
+    match command {
+        Command::Search => {
+            ui.new_search_popup();
+            return Ok(true);
+        }
+        Command::ClosePopup => {
+            // go back to following the playback
+            ui.popup = None;
+            if let PageState::Lyrics { line_list, .. } = ui.current_page_mut() {
+                line_list.select(None);
+            }
+            return Ok(true);
+        }
+        _ => {}
+    }
+
+    let offset = chrono::Duration::milliseconds(data.lyrics_offset(&track_uri));
+    let lines = ui.search_filtered_items(&lyrics.lines);
+    let len = lines.len();
+    let selected = match ui.current_page_mut().selected() {
+        Some(id) => id,
+        // start moving the cursor from the playing line
+        None => state
+            .player
+            .read()
+            .current_track_progress()
+            .and_then(|progress| lyrics.playing_line(progress + offset))
+            .unwrap_or_default(),
+    };
+    if selected >= len {
+        return Ok(false);
+    }
+
+    if command == Command::ChooseSelected {
+        // seek to the selected line, taking the lyrics offset into account
+        let position = std::cmp::max(lines[selected].time - offset, chrono::Duration::zero());
+        client_pub.send(ClientRequest::Player(PlayerRequest::SeekTrack(position)))?;
+        ui.popup = None;
+        if let PageState::Lyrics { line_list, .. } = ui.current_page_mut() {
+            line_list.select(None);
+        }
+        return Ok(true);
+    }
+
+    let count = ui.count_prefix;
+    Ok(handle_navigation_command(
+        command,
+        ui.current_page_mut(),
+        selected,
+        len,
+        count,
+    ))
+}
+
 fn handle_command_for_queue_page(command: Command, ui: &mut UIStateGuard) -> bool {
     let scroll_offset = match ui.current_page() {
         PageState::Queue { scroll_offset } => *scroll_offset,
@@ -25,14 +25,15 @@ pub fn handle_key_sequence_for_popup(
                 ui,
             );
         }
-        PopupState::UserPlaylistList(..) => {
-            if handle_key_sequence_for_playlist_search_popup(key_sequence, ui) {
-                return Ok(true);
-            }
-        }
         _ => {}
     }
 
+    if matches!(ui.popup, Some(PopupState::UserPlaylistList(..)))
+        && handle_key_sequence_for_playlist_search_popup(key_sequence, ui)
+    {
+        return Ok(true);
+    }
+
     let Some(command) = config::get_config()
         .keymap_config
         .find_command_from_key_sequence(key_sequence)
@@ -277,6 +278,28 @@ pub fn handle_key_sequence_for_popup(
                 },
             )
         }
+        PopupState::EqualizerPresetList(presets, _) => {
+            let n_items = presets.len();
+
+            handle_command_for_list_popup(
+                command,
+                ui,
+                n_items,
+                |_, _| {},
+                |ui: &mut UIStateGuard, id: usize| -> Result<()> {
+                    if let Some(PopupState::EqualizerPresetList(ref presets, _)) = ui.popup {
+                        client_pub.send(ClientRequest::LocalPlayer(
+                            LocalPlayerRequest::SetEqualizerPreset(presets[id].clone()),
+                        ))?;
+                    }
+                    ui.popup = None;
+                    Ok(())
+                },
+                |ui: &mut UIStateGuard| {
+                    ui.popup = None;
+                },
+            )
+        }
         PopupState::DeviceList(_) => {
             let player = state.player.read();
 
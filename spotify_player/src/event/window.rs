@@ -1,11 +1,12 @@
 use super::page::handle_navigation_command;
 use super::*;
 use crate::{
+    client::LocalPlayerRequest,
     command::{
         construct_album_actions, construct_artist_actions, construct_playlist_actions,
         construct_show_actions,
     },
-    state::{Episode, MutableWindowState, Show, UIStateGuard},
+    state::{Episode, LocalLibraryItem, MixedEntry, MutableWindowState, Show, UIStateGuard},
 };
 use command::Action;
 use rand::Rng;
@@ -241,6 +242,7 @@ fn handle_playlist_modify_command(
             }
             return Ok(true);
         }
+        Command::ShowActionsOnSelectedItem if tracks[id].is_local => return Ok(false),
         Command::ShowActionsOnSelectedItem => {
             let mut actions = command::construct_track_actions(tracks[id], data);
             actions.push(Action::DeleteFromPlaylist);
@@ -303,16 +305,75 @@ fn handle_command_for_track_table_window(
 
     match command {
         Command::PlayRandom | Command::ChooseSelected => {
-            let uri = if command == Command::PlayRandom {
-                tracks[rand::rng().random_range(0..tracks.len())].id.uri()
+            let track = if command == Command::PlayRandom {
+                &tracks[rand::rng().random_range(0..tracks.len())]
             } else {
-                filtered_tracks[id].id.uri()
+                filtered_tracks[id]
             };
 
-            let base_playback = match context_id {
-                None | Some(ContextId::Tracks(_)) => {
-                    Playback::URIs(tracks.iter().map(|t| t.id.clone().into()).collect(), None)
+            // contexts mixing Spotify tracks and local tracks are played in segments,
+            // each segment is played by either Spotify or the local player
+            if track.local_path.is_some() && tracks.iter().any(|t| !t.is_local)
+                || !track.is_local && tracks.iter().any(|t| t.local_path.is_some())
+            {
+                let n_unresolved = tracks
+                    .iter()
+                    .filter(|t| t.is_local && t.local_path.is_none())
+                    .count();
+                if n_unresolved > 0 {
+                    tracing::warn!(
+                        "Skipping {n_unresolved} local tracks not found in the local library"
+                    );
                 }
+                let entries = tracks
+                    .iter()
+                    .filter_map(|t| {
+                        if t.is_local {
+                            t.local_path.clone().map(MixedEntry::Local)
+                        } else {
+                            Some(MixedEntry::Spotify(t.id.clone()))
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let entry = match track.local_path {
+                    Some(ref path) => MixedEntry::Local(path.clone()),
+                    None => MixedEntry::Spotify(track.id.clone()),
+                };
+                let offset = entries.iter().position(|e| *e == entry).unwrap_or_default();
+                client_pub.send(ClientRequest::StartMixedPlayback { entries, offset })?;
+                return Ok(true);
+            }
+
+            // local tracks are played by the local player
+            if track.is_local {
+                let Some(ref path) = track.local_path else {
+                    tracing::warn!("Local track \"{track}\" is not found in the local library");
+                    return Ok(true);
+                };
+                let paths = tracks
+                    .iter()
+                    .filter_map(|t| t.local_path.clone())
+                    .collect::<Vec<_>>();
+                let offset = paths.iter().position(|p| p == path).unwrap_or_default();
+                client_pub.send(ClientRequest::LocalPlayer(
+                    LocalPlayerRequest::StartTracks {
+                        tracks: paths,
+                        offset,
+                    },
+                ))?;
+                return Ok(true);
+            }
+            let uri = track.id.uri();
+
+            let base_playback = match context_id {
+                None | Some(ContextId::Tracks(_)) => Playback::URIs(
+                    tracks
+                        .iter()
+                        .filter(|t| !t.is_local)
+                        .map(|t| t.id.clone().into())
+                        .collect(),
+                    None,
+                ),
                 Some(ContextId::Show(_)) => unreachable!(
                     "show context should be handled by handle_command_for_episode_table_window"
                 ),
@@ -325,6 +386,8 @@ fn handle_command_for_track_table_window(
                 None,
             )))?;
         }
+        // local tracks don't exist on Spotify, so no action can be done on them
+        Command::ShowActionsOnSelectedItem if filtered_tracks[id].is_local => return Ok(false),
         Command::ShowActionsOnSelectedItem => {
             let actions = command::construct_track_actions(filtered_tracks[id], data);
             ui.popup = Some(PopupState::ActionList(
@@ -333,9 +396,14 @@ fn handle_command_for_track_table_window(
             ));
         }
         Command::AddSelectedItemToQueue => {
-            client_pub.send(ClientRequest::AddPlayableToQueue(
-                filtered_tracks[id].id.clone().into(),
-            ))?;
+            let track = filtered_tracks[id];
+            if !track.is_local {
+                client_pub.send(ClientRequest::AddPlayableToQueue(track.id.clone().into()))?;
+            } else if let Some(ref path) = track.local_path {
+                client_pub.send(ClientRequest::LocalPlayer(LocalPlayerRequest::AddToQueue(
+                    path.clone(),
+                )))?;
+            }
         }
         Command::JumpToHighlightTrackInContext => {
             ui.popup = None;
@@ -479,6 +547,35 @@ pub fn handle_command_for_album_list_window(
     Ok(true)
 }
 
+pub fn handle_command_for_local_item_list_window(
+    command: Command,
+    items: &[&LocalLibraryItem],
+    ui: &mut UIStateGuard,
+    client_pub: &flume::Sender<ClientRequest>,
+) -> Result<bool> {
+    let id = ui.current_page_mut().selected().unwrap_or_default();
+    if id >= items.len() {
+        return Ok(false);
+    }
+
+    let count = ui.count_prefix;
+    if handle_navigation_command(command, ui.current_page_mut(), id, items.len(), count) {
+        return Ok(true);
+    }
+    match command {
+        Command::ChooseSelected => {
+            ui.new_page(PageState::Context {
+                id: None,
+                context_page_type: ContextPageType::Browsing(items[id].context_id()),
+                state: None,
+            });
+            client_pub.send(ClientRequest::GetLocalContext(items[id].clone()))?;
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
 pub fn handle_command_for_playlist_list_window(
     command: Command,
     playlists: &[&PlaylistFolderItem],
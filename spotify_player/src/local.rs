@@ -0,0 +1,282 @@
+use std::{path::Path, time::Duration};
+
+use crate::{
+    client::{LocalPlayerRequest, PlayerRequest},
+    config,
+    state::{LocalLibraryData, LocalPlayback, Mutex, SharedState, Track},
+};
+use anyhow::{Context, Result};
+use local_player::{
+    equalizer::Band,
+    index::{LibraryWatcher, ScanStats},
+    LocalPlayer, PlayerEvent,
+};
+
+/// sender of requests to the local player's thread, set once the player is started by the first request
+static LOCAL_PLAYER: Mutex<Option<flume::Sender<LocalPlayerRequest>>> = Mutex::new(None);
+
+/// Start a watcher that keeps the local library index in sync with the configured music directories
+pub fn start_library_watcher(state: &SharedState) -> Result<()> {
+    let configs = config::get_config();
+    let library_config = &configs.app_config.local_library;
+    if library_config.music_dirs.is_empty() {
+        return Ok(());
+    }
+
+    // the watcher is created before rescanning the library to not miss any changes during the scan
+    let watcher = if library_config.watch {
+        Some(LibraryWatcher::new(&library_config.music_dirs).context("create library watcher")?)
+    } else {
+        None
+    };
+
+    // rescan a copy of the index to avoid holding the data lock during a (possibly long) scan
+    let mut index = state.data.read().local_library.index.clone();
+    let stats = index.rescan(&library_config.music_dirs);
+    tracing::info!("Rescanned the local library: {stats:?}");
+    state.data.write().local_library = LocalLibraryData::new(index);
+    store_library_index(state, stats);
+
+    if let Some(watcher) = watcher {
+        while let Some(paths) = watcher.next_changes() {
+            // read the changed files before locking the data, tags reading can be slow for large batches
+            let changes = state.data.read().local_library.index.read_paths(&paths);
+            let stats = {
+                let mut data = state.data.write();
+                let stats = data.local_library.index.apply_changes(changes);
+                if stats.has_changes() {
+                    data.local_library.update_items();
+                }
+                stats
+            };
+            tracing::info!(
+                "Updated the local library with {} changed paths: {stats:?}",
+                paths.len()
+            );
+            store_library_index(state, stats);
+        }
+    }
+
+    Ok(())
+}
+
+fn store_library_index(state: &SharedState, stats: ScanStats) {
+    if !stats.has_changes() {
+        return;
+    }
+    let cache_folder = &config::get_config().cache_folder;
+    if let Err(err) = state.data.read().local_library.index.store(cache_folder) {
+        tracing::error!("Failed to store the local library index: {err:#}");
+    }
+}
+
+/// Send a request to the local player, starting the player's thread if it's not running
+pub fn send_player_request(state: &SharedState, request: LocalPlayerRequest) -> Result<()> {
+    let mut local_player = LOCAL_PLAYER.lock();
+    let sender = match local_player.as_ref() {
+        Some(sender) => sender,
+        None => local_player.insert(spawn_local_player(state)?),
+    };
+    if sender.send(request).is_err() {
+        // the player's thread stopped, it will be restarted by the next request
+        *local_player = None;
+        anyhow::bail!("local player is not running");
+    }
+    Ok(())
+}
+
+/// Switch the local player's equalizer preset, without starting the player if it's not running
+pub fn set_equalizer_preset(state: &SharedState, name: String) -> Result<()> {
+    state.player.write().equalizer_preset.clone_from(&name);
+    if let Some(sender) = LOCAL_PLAYER.lock().as_ref() {
+        sender
+            .send(LocalPlayerRequest::SetEqualizerPreset(name))
+            .context("local player is not running")?;
+    }
+    Ok(())
+}
+
+/// Spawn the local player's thread, returning the sender of requests to the player once it's created
+fn spawn_local_player(state: &SharedState) -> Result<flume::Sender<LocalPlayerRequest>> {
+    let (sender, receiver) = flume::unbounded();
+    let (created_tx, created_rx) = flume::bounded(1);
+    let state = state.clone();
+    std::thread::Builder::new()
+        .name("local-player-handler".to_string())
+        .spawn(move || match new_local_player(&state) {
+            Ok(player) => {
+                created_tx.send(Ok(())).unwrap_or_default();
+                start_local_player(&state, &player, &receiver);
+            }
+            Err(err) => created_tx.send(Err(err)).unwrap_or_default(),
+        })
+        .context("spawn local player handler thread")?;
+
+    created_rx
+        .recv()
+        .context("local player handler thread stopped")??;
+    Ok(sender)
+}
+
+enum LocalPlayerMessage {
+    Request(LocalPlayerRequest),
+    Event(PlayerEvent),
+}
+
+/// Create a local player with the configured settings
+fn new_local_player(state: &SharedState) -> Result<LocalPlayer> {
+    let configs = config::get_config();
+    let player_config = &configs.app_config.local_player;
+    let player =
+        LocalPlayer::with_output(&player_config.output()?).context("create local player")?;
+    player.set_volume(configs.app_config.device.volume);
+    player.set_gapless(player_config.gapless);
+    player.set_crossfade(Duration::from_secs(
+        player_config.crossfade_duration_secs.into(),
+    ));
+    player.set_normalization(player_config.normalization());
+    player.set_equalizer(equalizer_bands(&state.player.read().equalizer_preset));
+    Ok(player)
+}
+
+/// Start the local player, which handles local player requests and
+/// keeps the application's local playback state in sync with the player's events.
+///
+/// The player is owned by the calling thread because its audio output stream cannot be moved across threads.
+fn start_local_player(
+    state: &SharedState,
+    player: &LocalPlayer,
+    request_rx: &flume::Receiver<LocalPlayerRequest>,
+) {
+    let event_rx = player.events();
+    let mut muted_volume = None;
+
+    loop {
+        let message = flume::Selector::new()
+            .recv(request_rx, |r| r.ok().map(LocalPlayerMessage::Request))
+            .recv(&event_rx, |e| e.ok().map(LocalPlayerMessage::Event))
+            .wait();
+        match message {
+            Some(LocalPlayerMessage::Request(request)) => {
+                if let Err(err) = handle_player_request(state, player, request, &mut muted_volume) {
+                    tracing::error!("Failed to handle local player request: {err:#}");
+                }
+            }
+            Some(LocalPlayerMessage::Event(event)) => handle_player_event(state, player, event),
+            None => break,
+        }
+    }
+}
+
+fn handle_player_request(
+    state: &SharedState,
+    player: &LocalPlayer,
+    request: LocalPlayerRequest,
+    muted_volume: &mut Option<u8>,
+) -> Result<()> {
+    tracing::info!("Handling local player request: {request:?}");
+
+    match request {
+        LocalPlayerRequest::StartTracks { tracks, offset } => {
+            player.start_tracks(tracks, offset)?;
+        }
+        LocalPlayerRequest::AddToQueue(path) => player.add_to_queue(path),
+        LocalPlayerRequest::Stop => {
+            player.stop();
+            state.player.write().local_playback = None;
+        }
+        LocalPlayerRequest::SetEqualizerPreset(name) => {
+            player.set_equalizer(equalizer_bands(&name));
+        }
+        LocalPlayerRequest::Player(request) => match request {
+            PlayerRequest::NextTrack => player.next()?,
+            PlayerRequest::PreviousTrack => player.previous()?,
+            PlayerRequest::Resume => player.play()?,
+            PlayerRequest::Pause => player.pause(),
+            PlayerRequest::ResumePause => player.resume_pause()?,
+            PlayerRequest::SeekTrack(position) => player.seek(position.to_std()?)?,
+            PlayerRequest::Volume(volume) => {
+                player.set_volume(volume);
+                *muted_volume = None;
+            }
+            PlayerRequest::ToggleMute => {
+                if let Some(volume) = muted_volume.take() {
+                    player.set_volume(volume);
+                } else {
+                    *muted_volume = Some(player.volume());
+                    player.set_volume(0);
+                }
+            }
+            PlayerRequest::Repeat
+            | PlayerRequest::Shuffle
+            | PlayerRequest::TransferPlayback(..)
+            | PlayerRequest::StartPlayback(..) => {
+                tracing::warn!("Player request {request:?} is not supported by the local player");
+            }
+        },
+    }
+
+    if let Some(playback) = state.player.write().local_playback.as_mut() {
+        playback.volume = player.volume();
+    }
+    Ok(())
+}
+
+fn handle_player_event(state: &SharedState, player: &LocalPlayer, event: PlayerEvent) {
+    tracing::info!("Got a local player event: {event:?}");
+
+    match event {
+        PlayerEvent::Changed { path } => {
+            let track = local_track(state, &path);
+            state.player.write().local_playback = Some(LocalPlayback::new(track, player.volume()));
+        }
+        PlayerEvent::Playing { position_ms, .. } | PlayerEvent::Paused { position_ms, .. } => {
+            let is_playing = matches!(event, PlayerEvent::Playing { .. });
+            if let Some(playback) = state.player.write().local_playback.as_mut() {
+                playback.update(is_playing, Duration::from_millis(position_ms.into()));
+            }
+        }
+        PlayerEvent::EndOfTrack { .. } => {
+            if let Some(playback) = state.player.write().local_playback.as_mut() {
+                playback.update(false, playback.track.duration);
+            }
+        }
+    }
+}
+
+/// gets the equalizer bands of a preset, falling back to no bands if the preset doesn't exist
+fn equalizer_bands(name: &str) -> Vec<Band> {
+    let preset = config::get_config()
+        .app_config
+        .local_player
+        .find_equalizer_preset(name);
+    if let Some(preset) = preset {
+        preset.bands
+    } else {
+        tracing::warn!("Equalizer preset {name} not found");
+        vec![]
+    }
+}
+
+/// gets the track of a local audio file, reading the file's tags if the file is not indexed
+fn local_track(state: &SharedState, path: &Path) -> Track {
+    if let Some(track) = state.data.read().local_library.index.track(path) {
+        return Track::from_local_track(track);
+    }
+    match local_player::scanner::read_track(path) {
+        Ok(track) => Track::from_local_track(&track),
+        Err(err) => {
+            tracing::warn!("Failed to read track {}: {err:#}", path.display());
+            Track::from_local_track(&local_player::model::Track {
+                path: path.to_path_buf(),
+                name: path.display().to_string(),
+                artists: vec![],
+                album: None,
+                track_number: None,
+                disc_number: None,
+                duration: Duration::ZERO,
+                year: None,
+            })
+        }
+    }
+}
@@ -0,0 +1,324 @@
+use std::{
+    future::Future,
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    config,
+    state::{Lyrics, Track},
+};
+use anyhow::{Context, Result};
+use lyric_finder::lrc::Lrc;
+use rspotify::prelude::Id;
+use serde::{Deserialize, Serialize};
+
+/// the name of the lyrics cache's folder inside the cache folder
+const CACHE_FOLDER_NAME: &str = "lyrics";
+/// the expiry of cached lyrics
+const LYRICS_CACHE_EXPIRY: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// the expiry of cached "no lyrics" results, shorter as the lyrics may become available later
+const NO_LYRICS_CACHE_EXPIRY: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Serialize, Deserialize)]
+/// A cached lyrics lookup result of a track
+struct CacheEntry {
+    /// the time the lyrics were fetched, in seconds since the Unix epoch
+    fetched_at: u64,
+    /// the fetched lyrics, `None` if the track has no lyrics
+    lyrics: Option<CachedLyrics>,
+}
+
+impl CacheEntry {
+    /// gets the cached lyrics, `None` if the track is cached as having no lyrics
+    fn into_lyrics(self) -> Option<Lyrics> {
+        self.lyrics
+            .map(|l| Lyrics::from_lrc(Lrc::parse(&l.lrc), &l.source))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedLyrics {
+    /// the name of the lyrics' provider
+    source: String,
+    /// the lyrics in the LRC format
+    lrc: String,
+}
+
+/// gets the lyrics of a track from a local LRC file, if any.
+///
+/// Files in the configured lyrics folder take priority over the files next to local audio files.
+pub fn local_lyrics(track: &Track) -> Option<Lyrics> {
+    let path = lyrics_file(track, "lrc")?;
+    tracing::info!(
+        "Found local lyrics for \"{}\": {}",
+        track.name,
+        path.display()
+    );
+    let lrc = read_lrc_file(&path)?;
+    let source = path.file_name().unwrap_or_default().to_string_lossy();
+    Some(Lyrics::from_lrc(lrc, &source))
+}
+
+/// adds the translation of a track's lyrics from a local LRC file, if any.
+///
+/// Translation files are named after the lyrics files with the configured translation language,
+/// e.g. `<artist> - <title>.en.lrc` in the lyrics folder or `song.en.lrc` next to `song.mp3`.
+pub fn add_translation(track: &Track, lyrics: &mut Lyrics) {
+    let language = &config::get_config().app_config.lyrics.translation_language;
+    if language.is_empty() {
+        return;
+    }
+    let Some(path) = lyrics_file(track, &format!("{language}.lrc")) else {
+        return;
+    };
+    tracing::info!(
+        "Found lyrics translation for \"{}\": {}",
+        track.name,
+        path.display()
+    );
+    if let Some(lrc) = read_lrc_file(&path) {
+        lyrics.add_translation(lrc);
+    }
+}
+
+fn read_lrc_file(path: &Path) -> Option<Lrc> {
+    match std::fs::read(path) {
+        Ok(content) => Some(Lrc::parse(&String::from_utf8_lossy(&content))),
+        Err(err) => {
+            tracing::warn!("Failed to read lyrics file {}: {err:#}", path.display());
+            None
+        }
+    }
+}
+
+/// finds the lyrics file of a track with a given extension
+fn lyrics_file(track: &Track, extension: &str) -> Option<PathBuf> {
+    let configs = config::get_config();
+    if let Some(folder) = &configs.app_config.lyrics.folder {
+        if let Some(path) = file_names(track, extension)
+            .iter()
+            .find_map(|name| find_file(folder, name))
+        {
+            return Some(path);
+        }
+    }
+
+    let path = track.local_path.as_ref()?.with_extension(extension);
+    path.is_file().then_some(path)
+}
+
+/// gets the candidate file names of a track's lyrics file, in the `<artist> - <title>.<extension>` form
+/// with either the track's first artist or all its artists
+fn file_names(track: &Track, extension: &str) -> Vec<String> {
+    let mut artists = vec![];
+    if let Some(artist) = track.artists.first() {
+        artists.push(artist.name.clone());
+    }
+    if track.artists.len() > 1 {
+        artists.push(track.artists_info());
+    }
+
+    artists
+        .into_iter()
+        .map(|artists| sanitize(&format!("{artists} - {}.{extension}", track.name)))
+        .collect()
+}
+
+/// gets the file name of a track's exported lyrics,
+/// with the `.lrc` extension for synced lyrics and the `.txt` extension for unsynced lyrics
+pub fn export_file_name(track: &Track, lyrics: &Lyrics) -> String {
+    let extension = if lyrics.synced { "lrc" } else { "txt" };
+    sanitize(&format!(
+        "{} - {}.{extension}",
+        track.artists_info(),
+        track.name
+    ))
+}
+
+/// gets the content of a track's exported lyrics, in the LRC format for synced lyrics
+/// and in plain text for unsynced lyrics
+pub fn export_content(track: &Track, lyrics: &Lyrics) -> String {
+    let mut lrc = Lrc::from(lyrics);
+    if lyrics.synced {
+        lrc.tags = vec![
+            ("ar".to_string(), track.artists_info()),
+            ("ti".to_string(), track.name.clone()),
+        ];
+        if let Some(album) = &track.album {
+            lrc.tags.push(("al".to_string(), album.name.clone()));
+        }
+    }
+    lrc.serialize()
+}
+
+/// replaces the path separators in a file name
+fn sanitize(name: &str) -> String {
+    name.replace(['/', '\\'], "_")
+}
+
+/// finds a file in a folder by its name, falling back to a case-insensitive match
+fn find_file(folder: &Path, name: &str) -> Option<PathBuf> {
+    let path = folder.join(name);
+    if path.is_file() {
+        return Some(path);
+    }
+
+    let name = name.to_lowercase();
+    std::fs::read_dir(folder)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .is_some_and(|n| n.to_string_lossy().to_lowercase() == name)
+        })
+}
+
+/// gets the lyrics of a track from the on-disk lyrics cache, or fetches the lyrics with `fetch`
+/// and stores them into the cache.
+///
+/// Tracks without lyrics are cached too, but failed lookups are not cached so that they are retried later.
+pub async fn cached_or_fetch(
+    track: &Track,
+    fetch: impl Future<Output = Result<Option<Lyrics>>>,
+) -> Result<Option<Lyrics>> {
+    cached_or_fetch_at(&cache_file(track), fetch).await
+}
+
+async fn cached_or_fetch_at(
+    path: &Path,
+    fetch: impl Future<Output = Result<Option<Lyrics>>>,
+) -> Result<Option<Lyrics>> {
+    if let Some(entry) = read_cache_entry(path) {
+        return Ok(entry.into_lyrics());
+    }
+
+    let lyrics = fetch.await?;
+    if let Err(err) = write_cache_entry(path, lyrics.as_ref()) {
+        tracing::warn!("Failed to cache lyrics {}: {err:#}", path.display());
+    }
+    Ok(lyrics)
+}
+
+/// reads a cache entry from the on-disk lyrics cache.
+///
+/// Returns `None` if the entry doesn't exist or expired.
+fn read_cache_entry(path: &Path) -> Option<CacheEntry> {
+    if !path.exists() {
+        return None;
+    }
+
+    let result = std::fs::File::open(path)
+        .map_err(anyhow::Error::from)
+        .and_then(|f| Ok(serde_json::from_reader::<_, CacheEntry>(BufReader::new(f))?));
+    let entry = match result {
+        Ok(entry) => entry,
+        Err(err) => {
+            tracing::warn!("Failed to load cached lyrics {}: {err:#}", path.display());
+            return None;
+        }
+    };
+
+    let expiry = if entry.lyrics.is_some() {
+        LYRICS_CACHE_EXPIRY
+    } else {
+        NO_LYRICS_CACHE_EXPIRY
+    };
+    if now_secs().saturating_sub(entry.fetched_at) > expiry.as_secs() {
+        return None;
+    }
+
+    Some(entry)
+}
+
+/// writes a cache entry of fetched lyrics, `None` if the track has no lyrics, into the on-disk lyrics cache
+fn write_cache_entry(path: &Path, lyrics: Option<&Lyrics>) -> Result<()> {
+    if let Some(folder) = path.parent() {
+        std::fs::create_dir_all(folder).with_context(|| format!("create {}", folder.display()))?;
+    }
+
+    let entry = CacheEntry {
+        fetched_at: now_secs(),
+        lyrics: lyrics.map(|l| CachedLyrics {
+            source: l.source.clone(),
+            lrc: Lrc::from(l).serialize(),
+        }),
+    };
+    let f = BufWriter::new(
+        std::fs::File::create(path).with_context(|| format!("create {}", path.display()))?,
+    );
+    serde_json::to_writer(f, &entry).context("serialize cached lyrics")?;
+    Ok(())
+}
+
+/// removes the lyrics of a track from the on-disk lyrics cache
+pub fn remove_cached_lyrics(track: &Track) -> Result<()> {
+    let path = cache_file(track);
+    if path.exists() {
+        std::fs::remove_file(&path).with_context(|| format!("remove {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// gets the cache file of a track's lyrics, named after the track's URI
+fn cache_file(track: &Track) -> PathBuf {
+    let name = sanitize(&track.id.uri()).replace(':', "_");
+    config::get_config()
+        .cache_folder
+        .join(CACHE_FOLDER_NAME)
+        .join(format!("{name}.json"))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn failed_lookups_are_not_cached() {
+        let path = std::env::temp_dir().join(format!(
+            "spotify_player_lyrics_{}/track.json",
+            std::process::id()
+        ));
+
+        // a failed lookup is returned without being cached
+        let result = cached_or_fetch_at(&path, async { anyhow::bail!("network error") }).await;
+        assert!(result.is_err());
+        assert!(!path.exists());
+
+        // a track without lyrics is cached
+        let lyrics = cached_or_fetch_at(&path, async { Ok(None) }).await.unwrap();
+        assert!(lyrics.is_none());
+        assert!(read_cache_entry(&path).is_some_and(|entry| entry.lyrics.is_none()));
+        let lyrics = cached_or_fetch_at(&path, async { unreachable!("the lyrics are cached") })
+            .await
+            .unwrap();
+        assert!(lyrics.is_none());
+
+        // fetched lyrics are cached once the cached entry is removed
+        std::fs::remove_file(&path).unwrap();
+        let fetched = Lyrics::from_lrc(Lrc::parse("[00:01.00] line"), "LRCLIB");
+        cached_or_fetch_at(&path, async { Ok(Some(fetched)) })
+            .await
+            .unwrap();
+        let lyrics = cached_or_fetch_at(&path, async { unreachable!("the lyrics are cached") })
+            .await
+            .unwrap()
+            .unwrap();
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        assert_eq!(lyrics.source, "LRCLIB");
+        assert!(lyrics.synced);
+        assert_eq!(lyrics.lines[0].text, "line");
+    }
+}
@@ -5,6 +5,8 @@ mod command;
 mod config;
 mod event;
 mod key;
+mod local;
+mod lyrics;
 #[cfg(feature = "media-control")]
 mod media_control;
 mod playlist_folders;
@@ -154,6 +156,47 @@ async fn start_app(state: &state::SharedState) -> Result<()> {
         }
     }));
 
+    #[cfg(unix)]
+    tasks.push(tokio::task::spawn({
+        let client = client.clone();
+        let state = state.clone();
+        async move {
+            let path = configs.client_socket_path();
+            tracing::info!("Starting a client socket at {}", path.display());
+            match cli::bind_unix_socket(&path) {
+                Ok(listener) => cli::start_unix_socket(client, listener, Some(state)).await,
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed to create a Unix socket for handling CLI commands: {err:#}"
+                    );
+                }
+            }
+        }
+    }));
+
+    if let Some(port) = configs.app_config.client_tcp_port {
+        tasks.push(tokio::task::spawn({
+            let client = client.clone();
+            let state = state.clone();
+            async move {
+                tracing::info!("Starting a client TCP socket at 127.0.0.1:{port}");
+                match tokio::net::TcpListener::bind(("127.0.0.1", port)).await {
+                    Ok(listener) => cli::start_tcp_socket(client, listener, Some(state)).await,
+                    Err(err) => {
+                        tracing::warn!(
+                            "Failed to create a TCP socket for handling CLI commands: {err:#}"
+                        );
+                    }
+                }
+            }
+        }));
+    }
+
+    // playback events watcher task (for CLI event subscriptions)
+    tasks.push(tokio::task::spawn(state::start_event_watcher(
+        state.clone(),
+    )));
+
     // client event handler task
     tasks.push(tokio::task::spawn({
         let state = state.clone();
@@ -171,6 +214,16 @@ async fn start_app(state: &state::SharedState) -> Result<()> {
         }
     }));
 
+    // local library watcher task
+    tokio::task::spawn_blocking({
+        let state = state.clone();
+        move || {
+            if let Err(err) = local::start_library_watcher(&state) {
+                tracing::error!("Failed to start the local library watcher: {err:#}");
+            }
+        }
+    });
+
     if !state.is_daemon {
         // spawn tasks needed for running the application UI
 
@@ -1,12 +1,13 @@
 use std::io::{BufReader, BufWriter};
 use std::{collections::HashMap, path::Path};
 
+use local_player::index::LibraryIndex;
 use serde::{de::DeserializeOwned, Serialize};
 use std::sync::LazyLock;
 
 use super::model::{
-    Album, Artist, Category, Context, ContextId, Id, Playlist, PlaylistFolderItem,
-    PlaylistFolderNode, SearchResults, Show, Track,
+    Album, Artist, Category, Context, ContextId, Id, LocalLibraryItem, Playlist,
+    PlaylistFolderItem, PlaylistFolderNode, SearchResults, Show, Track,
 };
 use super::Lyrics;
 
@@ -20,8 +21,12 @@ pub enum FileCacheKey {
     SavedShows,
     SavedAlbums,
     SavedTracks,
+    LyricsOffsets,
 }
 
+/// the step of lyrics offset adjustments in milliseconds
+pub const LYRICS_OFFSET_STEP_MS: i64 = 100;
+
 /// default time-to-live cache duration
 pub static TTL_CACHE_DURATION: LazyLock<std::time::Duration> =
     LazyLock::new(|| std::time::Duration::from_secs(60 * 60));
@@ -31,6 +36,10 @@ pub struct AppData {
     pub user_data: UserData,
     pub caches: MemoryCaches,
     pub browse: BrowseData,
+    pub local_library: LocalLibraryData,
+    /// lyrics offsets in milliseconds keyed by track URIs.
+    /// A positive offset shows the lyrics sooner.
+    pub lyrics_offsets: HashMap<String, i64>,
 }
 
 #[derive(Debug)]
@@ -55,6 +64,13 @@ pub struct MemoryCaches {
     pub images: ttl_cache::TtlCache<String, image::DynamicImage>,
 }
 
+/// local music library's data
+pub struct LocalLibraryData {
+    pub index: LibraryIndex,
+    /// browsable albums and artists of the library, derived from the index
+    pub items: Vec<LocalLibraryItem>,
+}
+
 #[derive(Default, Debug)]
 /// Spotify browse data
 pub struct BrowseData {
@@ -81,6 +97,27 @@ impl AppData {
             user_data: UserData::new_from_file_caches(cache_folder),
             caches: MemoryCaches::new(),
             browse: BrowseData::default(),
+            local_library: LocalLibraryData::new(LibraryIndex::load(cache_folder)),
+            lyrics_offsets: load_data_from_file_cache(FileCacheKey::LyricsOffsets, cache_folder)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Get the lyrics offset of a given track in milliseconds
+    pub fn lyrics_offset(&self, track_uri: &str) -> i64 {
+        self.lyrics_offsets
+            .get(track_uri)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Shift the lyrics offset of a given track by `delta` milliseconds
+    pub fn shift_lyrics_offset(&mut self, track_uri: &str, delta: i64) {
+        let offset = self.lyrics_offset(track_uri) + delta;
+        if offset == 0 {
+            self.lyrics_offsets.remove(track_uri);
+        } else {
+            self.lyrics_offsets.insert(track_uri.to_string(), offset);
         }
     }
 
@@ -117,6 +154,67 @@ impl AppData {
     }
 }
 
+impl LocalLibraryData {
+    pub fn new(index: LibraryIndex) -> Self {
+        let mut data = Self {
+            index,
+            items: vec![],
+        };
+        data.update_items();
+        data
+    }
+
+    /// updates the library's browsable items based on the library index
+    pub fn update_items(&mut self) {
+        self.items = self
+            .index
+            .albums()
+            .into_iter()
+            .map(|a| LocalLibraryItem::Album(a.clone()))
+            .chain(
+                self.index
+                    .artists()
+                    .into_iter()
+                    .map(|a| LocalLibraryItem::Artist(a.clone())),
+            )
+            .collect();
+    }
+
+    /// resolves local file entries of Spotify playlists to audio files in the local library
+    pub fn resolve_tracks(&self, tracks: &mut [Track]) {
+        for track in tracks
+            .iter_mut()
+            .filter(|t| t.is_local && t.local_path.is_none())
+        {
+            let artists = track
+                .artists
+                .iter()
+                .map(|a| a.name.as_str())
+                .collect::<Vec<_>>();
+            let Some(local_track) =
+                self.index
+                    .find_track(&track.name, &artists, &track.album_info(), track.duration)
+            else {
+                tracing::info!("Local track \"{track}\" is not found in the local library");
+                continue;
+            };
+
+            let added_at = track.added_at;
+            *track = Track::from_local_track(local_track);
+            track.added_at = added_at;
+        }
+    }
+
+    /// gets the tracks of a library item
+    pub fn item_tracks(&self, item: &LocalLibraryItem) -> Vec<Track> {
+        let tracks = match item {
+            LocalLibraryItem::Album(album) => self.index.album_tracks(&album.id),
+            LocalLibraryItem::Artist(artist) => self.index.artist_tracks(&artist.id),
+        };
+        tracks.into_iter().map(Track::from_local_track).collect()
+    }
+}
+
 impl UserData {
     /// Construct a new user data based on file caches
     pub fn new_from_file_caches(cache_folder: &Path) -> Self {
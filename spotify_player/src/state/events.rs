@@ -0,0 +1,192 @@
+use rspotify::model::{PlayableItem, RepeatState};
+use rspotify::prelude::Id;
+use serde::Serialize;
+
+use super::{Mutex, PlayerState, SharedState};
+
+/// the capacity of the playback events' channel, events are dropped for lagging subscribers
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+/// the interval between two checks of the player state's changes
+const WATCH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+/// An event of a playback change, sent to the CLI's subscribers
+pub enum PlaybackEvent {
+    TrackChanged { uri: Option<String> },
+    PlayPause { is_playing: bool },
+    VolumeChanged { volume: Option<u32> },
+    ShuffleChanged { shuffle: bool },
+    RepeatChanged { repeat: RepeatState },
+    DeviceChanged { id: Option<String>, name: String },
+    QueueChanged { uris: Vec<String> },
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+/// The playback's state tracked to detect changes
+struct Snapshot {
+    track: Option<String>,
+    is_playing: bool,
+    volume: Option<u32>,
+    shuffle: bool,
+    repeat: Option<RepeatState>,
+    device: Option<(Option<String>, String)>,
+    queue: Vec<String>,
+}
+
+impl Snapshot {
+    fn new(player: &PlayerState) -> Self {
+        let mut snapshot = Self::default();
+
+        if let Some(playback) = player.current_playback() {
+            snapshot.track = playback.item.as_ref().and_then(playable_uri);
+            snapshot.is_playing = playback.is_playing;
+            snapshot.volume = playback.device.volume_percent;
+            snapshot.shuffle = playback.shuffle_state;
+            snapshot.repeat = Some(playback.repeat_state);
+            snapshot.device = Some((playback.device.id, playback.device.name));
+        }
+        if let Some(queue) = &player.queue {
+            snapshot.queue = queue.queue.iter().filter_map(playable_uri).collect();
+        }
+        // the local player takes over the Spotify playback while playing
+        if let Some(playback) = &player.local_playback {
+            snapshot.track = Some(playback.track.id.uri());
+            snapshot.is_playing = playback.is_playing;
+            snapshot.volume = Some(u32::from(playback.volume));
+        }
+
+        snapshot
+    }
+
+    /// gets the events of the changes from another snapshot
+    fn events_since(&self, old: &Self) -> Vec<PlaybackEvent> {
+        let mut events = vec![];
+        if self.device != old.device {
+            if let Some((id, name)) = &self.device {
+                events.push(PlaybackEvent::DeviceChanged {
+                    id: id.clone(),
+                    name: name.clone(),
+                });
+            }
+        }
+        if self.track != old.track {
+            events.push(PlaybackEvent::TrackChanged {
+                uri: self.track.clone(),
+            });
+        }
+        if self.is_playing != old.is_playing {
+            events.push(PlaybackEvent::PlayPause {
+                is_playing: self.is_playing,
+            });
+        }
+        if self.volume != old.volume {
+            events.push(PlaybackEvent::VolumeChanged {
+                volume: self.volume,
+            });
+        }
+        if self.shuffle != old.shuffle {
+            events.push(PlaybackEvent::ShuffleChanged {
+                shuffle: self.shuffle,
+            });
+        }
+        if self.repeat != old.repeat {
+            if let Some(repeat) = self.repeat {
+                events.push(PlaybackEvent::RepeatChanged { repeat });
+            }
+        }
+        if self.queue != old.queue {
+            events.push(PlaybackEvent::QueueChanged {
+                uris: self.queue.clone(),
+            });
+        }
+        events
+    }
+
+    #[cfg(feature = "streaming")]
+    /// updates the snapshot with an event
+    fn apply(&mut self, event: &PlaybackEvent) {
+        match event {
+            PlaybackEvent::TrackChanged { uri } => self.track.clone_from(uri),
+            PlaybackEvent::PlayPause { is_playing } => self.is_playing = *is_playing,
+            PlaybackEvent::VolumeChanged { volume } => self.volume = *volume,
+            PlaybackEvent::ShuffleChanged { shuffle } => self.shuffle = *shuffle,
+            PlaybackEvent::RepeatChanged { repeat } => self.repeat = Some(*repeat),
+            PlaybackEvent::DeviceChanged { id, name } => {
+                self.device = Some((id.clone(), name.clone()));
+            }
+            PlaybackEvent::QueueChanged { uris } => self.queue.clone_from(uris),
+        }
+    }
+}
+
+fn playable_uri(item: &PlayableItem) -> Option<String> {
+    match item {
+        PlayableItem::Track(track) => track.id.as_ref().map(Id::uri),
+        PlayableItem::Episode(episode) => Some(episode.id.uri()),
+        PlayableItem::Unknown(_) => None,
+    }
+}
+
+/// Publisher of playback events to the CLI's subscribers
+pub struct EventPublisher {
+    sender: tokio::sync::broadcast::Sender<PlaybackEvent>,
+    /// the playback's state as of the last published event,
+    /// `None` if there was no subscriber
+    snapshot: Mutex<Option<Snapshot>>,
+}
+
+impl Default for EventPublisher {
+    fn default() -> Self {
+        Self {
+            sender: tokio::sync::broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            snapshot: Mutex::new(None),
+        }
+    }
+}
+
+impl EventPublisher {
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<PlaybackEvent> {
+        self.sender.subscribe()
+    }
+
+    #[cfg(feature = "streaming")]
+    /// publishes an event, unless the event doesn't change the playback's state
+    pub fn publish(&self, event: PlaybackEvent) {
+        let mut snapshot = self.snapshot.lock();
+        let Some(snapshot) = snapshot.as_mut() else {
+            return;
+        };
+
+        let old = snapshot.clone();
+        snapshot.apply(&event);
+        if *snapshot != old {
+            self.sender.send(event).unwrap_or_default();
+        }
+    }
+
+    /// publishes the events of the player state's changes since the last check
+    pub fn publish_changes(&self, player: &PlayerState) {
+        let mut snapshot = self.snapshot.lock();
+        if self.sender.receiver_count() == 0 {
+            *snapshot = None;
+            return;
+        }
+
+        let new = Snapshot::new(player);
+        if let Some(old) = snapshot.as_ref() {
+            for event in new.events_since(old) {
+                self.sender.send(event).unwrap_or_default();
+            }
+        }
+        *snapshot = Some(new);
+    }
+}
+
+/// starts a watcher publishing playback events from the changes of the player state
+pub async fn start_event_watcher(state: SharedState) {
+    loop {
+        tokio::time::sleep(WATCH_INTERVAL).await;
+        state.events.publish_changes(&state.player.read());
+    }
+}
@@ -1,11 +1,13 @@
 mod constant;
 mod data;
+mod events;
 mod model;
 mod player;
 mod ui;
 
 pub use constant::*;
 pub use data::*;
+pub use events::*;
 pub use model::*;
 pub use player::*;
 pub use ui::*;
@@ -22,6 +24,7 @@ pub struct State {
     pub ui: Mutex<UIState>,
     pub player: RwLock<PlayerState>,
     pub data: RwLock<AppData>,
+    pub events: EventPublisher,
 
     pub is_daemon: bool,
 }
@@ -37,11 +40,16 @@ impl State {
         }
 
         let app_data = AppData::new(&configs.cache_folder);
+        let player = PlayerState {
+            equalizer_preset: configs.app_config.local_player.equalizer_preset.clone(),
+            ..Default::default()
+        };
 
         Self {
             ui: Mutex::new(ui),
-            player: RwLock::new(PlayerState::default()),
+            player: RwLock::new(player),
             data: RwLock::new(app_data),
+            events: EventPublisher::default(),
             is_daemon,
         }
     }
@@ -128,6 +128,13 @@ pub struct PlaybackMetadata {
     pub fake_track_repeat_state: bool,
 }
 
+#[derive(Debug, Clone)]
+/// A browsable item of the local library
+pub enum LocalLibraryItem {
+    Album(local_player::model::Album),
+    Artist(local_player::model::Artist),
+}
+
 #[derive(Debug, Clone)]
 /// A Spotify device
 pub struct Device {
@@ -146,6 +153,12 @@ pub struct Track {
     pub explicit: bool,
     #[serde(skip)]
     pub added_at: u64,
+    /// whether the track is a local file rather than a Spotify track
+    #[serde(default)]
+    pub is_local: bool,
+    /// the local track's audio file, if found in the local library
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_path: Option<std::path::PathBuf>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -259,7 +272,7 @@ impl Context {
                 tracks.len(),
                 play_time(tracks),
             ),
-            Context::Artist { ref artist, .. } => artist.name.to_string(),
+            Context::Artist { ref artist, .. } => artist.name.clone(),
             Context::Tracks { desc, tracks } => {
                 format!("{} | {} songs | {}", desc, tracks.len(), play_time(tracks))
             }
@@ -308,6 +321,32 @@ impl ContextId {
     }
 }
 
+impl LocalLibraryItem {
+    /// gets the ID of the context listing the item's tracks
+    pub fn context_id(&self) -> ContextId {
+        ContextId::Tracks(match self {
+            Self::Album(album) => TracksId::new(&album.id, "Local Album"),
+            Self::Artist(artist) => TracksId::new(&artist.id, "Local Artist"),
+        })
+    }
+}
+
+impl std::fmt::Display for LocalLibraryItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Album(album) => write!(
+                f,
+                "[Album] {} • {}",
+                album.name,
+                map_join(&album.artists, |a| &a.name, ", ")
+            ),
+            Self::Artist(artist) => write!(f, "[Artist] {}", artist.name),
+        }
+    }
+}
+
+impl BidiDisplay for LocalLibraryItem {}
+
 impl TrackOrder {
     pub fn compare(&self, x: &Track, y: &Track) -> std::cmp::Ordering {
         match *self {
@@ -368,6 +407,8 @@ impl Track {
                 duration: track.duration.to_std().expect("valid chrono duration"),
                 explicit: track.explicit,
                 added_at: 0,
+                is_local: false,
+                local_path: None,
             })
         } else {
             None
@@ -392,6 +433,8 @@ impl Track {
                 duration: track.duration.to_std().expect("valid chrono duration"),
                 explicit: track.explicit,
                 added_at: added_at.map(|t| t.timestamp() as u64).unwrap_or_default(),
+                is_local: false,
+                local_path: None,
             })
         } else {
             None
@@ -403,12 +446,78 @@ impl Track {
         Track::try_from_full_track_with_date(track, None)
     }
 
+    /// converts a track from the local library into `Track`
+    pub fn from_local_track(track: &local_player::model::Track) -> Self {
+        Self {
+            id: TrackId::from_id(local_id(&track.path.to_string_lossy()))
+                .expect("valid local track id"),
+            name: track.name.clone(),
+            artists: track
+                .artists
+                .iter()
+                .map(Artist::from_local_artist)
+                .collect(),
+            album: track.album.as_ref().map(Album::from_local_album),
+            duration: track.duration,
+            explicit: false,
+            added_at: 0,
+            is_local: true,
+            local_path: Some(track.path.clone()),
+        }
+    }
+
+    /// converts a local file entry of a Spotify playlist into `Track`.
+    ///
+    /// Local file entries don't have Spotify IDs, so IDs are derived from the entries' metadata,
+    /// the same way as for tracks from the local library.
+    /// The returned track doesn't have an audio file until it's resolved using the local library.
+    fn from_local_playlist_track(
+        track: rspotify::model::FullTrack,
+        added_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> Self {
+        let artists = track
+            .artists
+            .into_iter()
+            .map(|a| local_player::model::Artist::new(a.name))
+            .collect::<Vec<_>>();
+        let album = (!track.album.name.is_empty()).then(|| {
+            local_player::model::Album::new(
+                track.album.name,
+                artists.clone(),
+                track.album.release_date.unwrap_or_default(),
+            )
+        });
+        let duration = track.duration.to_std().unwrap_or_default();
+
+        Self {
+            id: TrackId::from_id(local_id(&format!(
+                "spotify:local:{}:{}:{}:{}",
+                map_join(&artists, |a| &a.name, ","),
+                album.as_ref().map(|a| a.name.as_str()).unwrap_or_default(),
+                track.name,
+                duration.as_secs()
+            )))
+            .expect("valid local track id"),
+            name: track.name,
+            artists: artists.iter().map(Artist::from_local_artist).collect(),
+            album: album.as_ref().map(Album::from_local_album),
+            duration,
+            explicit: false,
+            added_at: added_at.map(|t| t.timestamp() as u64).unwrap_or_default(),
+            is_local: true,
+            local_path: None,
+        }
+    }
+
     /// tries to convert from a `rspotify::model::PlaylistItem` into `Track`
     pub fn try_from_playlist_item(item: rspotify::model::PlaylistItem) -> Option<Self> {
         let rspotify::model::PlayableItem::Track(track) = item.track? else {
             return None;
         };
 
+        if track.is_local {
+            return Some(Track::from_local_playlist_track(track, item.added_at));
+        }
         Track::try_from_full_track_with_date(track, item.added_at)
     }
 }
@@ -448,6 +557,22 @@ impl Album {
         })
     }
 
+    /// converts an album from the local library into `Album`
+    pub fn from_local_album(album: &local_player::model::Album) -> Self {
+        Self {
+            id: AlbumId::from_id(local_id(&album.id)).expect("valid local album id"),
+            name: album.name.clone(),
+            release_date: album.release_date.clone(),
+            artists: album
+                .artists
+                .iter()
+                .map(Artist::from_local_artist)
+                .collect(),
+            typ: None,
+            added_at: 0,
+        }
+    }
+
     /// gets the album's release year
     pub fn year(&self) -> String {
         self.release_date
@@ -511,6 +636,16 @@ impl Artist {
     }
 }
 
+impl Artist {
+    /// converts an artist from the local library into `Artist`
+    pub fn from_local_artist(artist: &local_player::model::Artist) -> Self {
+        Self {
+            id: ArtistId::from_id(local_id(&artist.id)).expect("valid local artist id"),
+            name: artist.name.clone(),
+        }
+    }
+}
+
 impl From<rspotify::model::FullArtist> for Artist {
     fn from(artist: rspotify::model::FullArtist) -> Self {
         Self {
@@ -526,6 +661,20 @@ impl std::fmt::Display for Artist {
     }
 }
 
+/// a helper function to construct a Spotify-compatible ID for an item from the local library.
+///
+/// Local items don't have Spotify IDs, so an ID is derived from a hash of the item's local ID.
+/// The IDs key persisted data, e.g. the cached lyrics, so the hash (FNV-1a) must be stable across builds.
+fn local_id(id: &str) -> String {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+    let hash = id.bytes().fold(FNV_OFFSET_BASIS, |hash, b| {
+        (hash ^ u64::from(b)).wrapping_mul(FNV_PRIME)
+    });
+    format!("local{hash:016x}")
+}
+
 /// a helper function to convert a vector of `rspotify::model::SimplifiedArtist`
 /// into a vector of `Artist`.
 fn from_simplified_artists_to_artists(
@@ -740,14 +889,143 @@ impl PlaybackMetadata {
     }
 }
 
+/// the maximum difference in milliseconds between the timestamps of a lyrics line and its translation
+const TRANSLATION_MAX_TIME_DIFF_MS: i64 = 1000;
+
 #[derive(Debug)]
 pub struct Lyrics {
     /// Timestamped lines
-    pub lines: Vec<(chrono::Duration, String)>,
+    pub lines: Vec<LyricsLine>,
+    /// Whether the lines' timestamps are synced with the track.
+    /// Lines of unsynced lyrics are all timestamped at zero.
+    pub synced: bool,
+    /// The name of the lyrics' provider
+    pub source: String,
+}
+
+#[derive(Debug)]
+/// A timestamped line of lyrics
+pub struct LyricsLine {
+    pub time: chrono::Duration,
+    pub text: String,
+    /// Timestamped words of the line, empty if the lyrics have no word-level timestamps
+    pub words: Vec<(chrono::Duration, String)>,
+    /// The line's translation, if any
+    pub translation: Option<String>,
+}
+
+impl std::fmt::Display for LyricsLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl Lyrics {
+    /// gets the index of the line playing at a given position, if any
+    pub fn playing_line(&self, position: chrono::Duration) -> Option<usize> {
+        self.lines
+            .partition_point(|l| l.time <= position)
+            .checked_sub(1)
+    }
+
+    /// checks if the lyrics have a translation
+    pub fn has_translation(&self) -> bool {
+        self.lines.iter().any(|l| l.translation.is_some())
+    }
+
+    /// adds the translation of the lyrics' lines.
+    ///
+    /// Synced lines are aligned with the translation's lines by their timestamps,
+    /// and unsynced lines are aligned with the translation's lines in order.
+    pub fn add_translation(&mut self, translation: lyric_finder::lrc::Lrc) {
+        if !self.synced || !translation.synced {
+            for (line, t) in self.lines.iter_mut().zip(translation.lines) {
+                line.translation = Some(t.text);
+            }
+            return;
+        }
+
+        for line in &mut self.lines {
+            let closest = translation
+                .lines
+                .iter()
+                .map(|t| {
+                    let time = chrono::Duration::from_std(t.time).unwrap_or_default();
+                    ((time - line.time).abs(), t)
+                })
+                .min_by_key(|(diff, _)| *diff);
+            if let Some((diff, t)) = closest {
+                if diff <= chrono::Duration::milliseconds(TRANSLATION_MAX_TIME_DIFF_MS) {
+                    line.translation = Some(t.text.clone());
+                }
+            }
+        }
+    }
+
+    /// creates lyrics from their LRC representation
+    pub fn from_lrc(lrc: lyric_finder::lrc::Lrc, source: &str) -> Self {
+        Self {
+            lines: lrc
+                .lines
+                .into_iter()
+                .map(|l| LyricsLine {
+                    time: chrono::Duration::from_std(l.time).unwrap_or_default(),
+                    text: l.text,
+                    words: l
+                        .words
+                        .into_iter()
+                        .map(|w| {
+                            (
+                                chrono::Duration::from_std(w.time).unwrap_or_default(),
+                                w.text,
+                            )
+                        })
+                        .collect(),
+                    translation: None,
+                })
+                .collect(),
+            synced: lrc.synced,
+            source: source.to_string(),
+        }
+    }
+}
+
+impl From<&Lyrics> for lyric_finder::lrc::Lrc {
+    fn from(value: &Lyrics) -> Self {
+        Self {
+            tags: vec![],
+            lines: value
+                .lines
+                .iter()
+                .map(|l| lyric_finder::lrc::LrcLine {
+                    time: l.time.to_std().unwrap_or_default(),
+                    text: l.text.clone(),
+                    words: l
+                        .words
+                        .iter()
+                        .map(|(t, text)| lyric_finder::lrc::LrcWord {
+                            time: t.to_std().unwrap_or_default(),
+                            text: text.clone(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            synced: value.synced,
+        }
+    }
 }
 
 impl From<librespot_metadata::lyrics::Lyrics> for Lyrics {
     fn from(value: librespot_metadata::lyrics::Lyrics) -> Self {
+        let synced = matches!(
+            value.lyrics.sync_type,
+            librespot_metadata::lyrics::SyncType::LineSynced
+        );
+        let source = if value.lyrics.provider_display_name.is_empty() {
+            "Spotify".to_string()
+        } else {
+            value.lyrics.provider_display_name
+        };
         let mut lines = value
             .lyrics
             .lines
@@ -757,10 +1035,32 @@ impl From<librespot_metadata::lyrics::Lyrics> for Lyrics {
                     l.start_time_ms.parse::<i64>().expect("invalid number"),
                 );
 
-                (t, to_bidi_string(&l.words))
+                LyricsLine {
+                    time: t,
+                    text: l.words,
+                    words: vec![],
+                    translation: None,
+                }
             })
             .collect::<Vec<_>>();
-        lines.sort_by_key(|l| l.0);
-        Self { lines }
+        lines.sort_by_key(|l| l.time);
+        Self {
+            lines,
+            synced,
+            source,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_ids_are_stable() {
+        assert_eq!(local_id(""), "localcbf29ce484222325");
+        assert_eq!(local_id("a"), "localaf63dc4c8601ec8c");
+        assert_eq!(local_id("/music/song.mp3"), local_id("/music/song.mp3"));
+        assert_ne!(local_id("/music/a.mp3"), local_id("/music/b.mp3"));
     }
 }
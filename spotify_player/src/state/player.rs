@@ -1,4 +1,6 @@
-use super::model::{AlbumId, ArtistId, ContextId, Device, PlaybackMetadata, PlaylistId, ShowId};
+use super::model::{
+    AlbumId, ArtistId, ContextId, Device, PlaybackMetadata, PlaylistId, ShowId, Track, TrackId,
+};
 
 /// Player state
 #[derive(Default, Debug)]
@@ -12,6 +14,119 @@ pub struct PlayerState {
     pub buffered_playback: Option<PlaybackMetadata>,
 
     pub queue: Option<rspotify::model::CurrentUserQueue>,
+
+    /// The playback of the local player, which takes over the Spotify playback while playing
+    pub local_playback: Option<LocalPlayback>,
+    /// The name of the local player's equalizer preset
+    pub equalizer_preset: String,
+    /// The playback of a context mixing Spotify tracks and local tracks
+    pub mixed_playback: Option<MixedPlayback>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// An entry of a context mixing Spotify tracks and local tracks
+pub enum MixedEntry {
+    Spotify(TrackId<'static>),
+    Local(std::path::PathBuf),
+}
+
+#[derive(Debug)]
+/// Playback of a context mixing Spotify tracks and local tracks.
+///
+/// The context is played in segments of consecutive entries of the same kind. Each segment is played
+/// by either Spotify or the local player, and the next segment is started when the current one ends.
+pub struct MixedPlayback {
+    entries: Vec<MixedEntry>,
+    /// the range of the currently playing segment in the entries
+    segment: std::ops::Range<usize>,
+    /// the maximum number of Spotify tracks in a segment
+    limit: usize,
+    /// whether the last entry of the current segment has started playing
+    pub last_entry_started: bool,
+}
+
+impl MixedPlayback {
+    pub fn new(entries: Vec<MixedEntry>, offset: usize, limit: usize) -> Self {
+        let segment = segment_at(&entries, offset, limit);
+        Self {
+            entries,
+            segment,
+            limit,
+            last_entry_started: false,
+        }
+    }
+
+    /// gets the entries of the currently playing segment, which are of the same kind
+    pub fn segment(&self) -> &[MixedEntry] {
+        &self.entries[self.segment.clone()]
+    }
+
+    /// moves to the next segment, returns `false` if the current segment is the last one
+    pub fn advance(&mut self) -> bool {
+        if self.segment.end >= self.entries.len() {
+            return false;
+        }
+        self.segment = segment_at(&self.entries, self.segment.end, self.limit);
+        self.last_entry_started = false;
+        true
+    }
+}
+
+/// gets the range of the segment starting at an entry, Spotify segments are limited
+/// to `limit` tracks to avoid the payload limit of the `start_playback` API request
+fn segment_at(entries: &[MixedEntry], start: usize, limit: usize) -> std::ops::Range<usize> {
+    let is_local = |e: &MixedEntry| matches!(e, MixedEntry::Local(_));
+    let Some(first) = entries.get(start) else {
+        return start..start;
+    };
+    let mut end = start
+        + entries[start..]
+            .iter()
+            .take_while(|e| is_local(e) == is_local(first))
+            .count();
+    if !is_local(first) {
+        end = end.min(start + limit.max(1));
+    }
+    start..end
+}
+
+#[derive(Debug, Clone)]
+/// Local player's playback
+pub struct LocalPlayback {
+    pub track: Track,
+    pub is_playing: bool,
+    pub volume: u8,
+    progress: std::time::Duration,
+    last_updated_time: std::time::Instant,
+}
+
+impl LocalPlayback {
+    pub fn new(track: Track, volume: u8) -> Self {
+        Self {
+            track,
+            is_playing: false,
+            volume,
+            progress: std::time::Duration::ZERO,
+            last_updated_time: std::time::Instant::now(),
+        }
+    }
+
+    /// Update the playback's state based on a player event
+    pub fn update(&mut self, is_playing: bool, progress: std::time::Duration) {
+        self.is_playing = is_playing;
+        self.progress = progress;
+        self.last_updated_time = std::time::Instant::now();
+    }
+
+    /// Get the playback's progress, estimated based on the last update time
+    pub fn progress(&self) -> std::time::Duration {
+        let progress = if self.is_playing {
+            self.progress + self.last_updated_time.elapsed()
+        } else {
+            self.progress
+        };
+        progress.min(self.track.duration)
+    }
 }
 
 impl PlayerState {
@@ -68,6 +183,27 @@ impl PlayerState {
         }
     }
 
+    /// gets the currently playing track, which is the local player's track while it is playing
+    pub fn current_track(&self) -> Option<Track> {
+        if let Some(ref playback) = self.local_playback {
+            return Some(playback.track.clone());
+        }
+        match self.currently_playing()? {
+            rspotify::model::PlayableItem::Track(track) => {
+                Track::try_from_full_track(track.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// gets the progress of the current track, which is the local player's progress while it is playing
+    pub fn current_track_progress(&self) -> Option<chrono::Duration> {
+        if let Some(ref playback) = self.local_playback {
+            return chrono::Duration::from_std(playback.progress()).ok();
+        }
+        self.playback_progress()
+    }
+
     pub fn playing_context_id(&self) -> Option<ContextId> {
         match self.playback {
             Some(ref playback) => match playback.context {
@@ -24,6 +24,13 @@ pub struct ImageRenderInfo {
     pub rendered: bool,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LyricsTranslationMode {
+    Original,
+    Translation,
+    Both,
+}
+
 /// Application's UI state
 #[derive(Debug)]
 pub struct UIState {
@@ -42,6 +49,9 @@ pub struct UIState {
     /// Count prefix for vim-style navigation (e.g., 5j, 10k)
     pub count_prefix: Option<usize>,
 
+    /// The lyrics shown in the lyrics page for lyrics with a translation
+    pub lyrics_translation_mode: LyricsTranslationMode,
+
     #[cfg(feature = "image")]
     pub last_cover_image_render_info: ImageRenderInfo,
 }
@@ -99,6 +109,17 @@ impl UIState {
 
 use ratatui::layout::Rect;
 
+impl LyricsTranslationMode {
+    /// gets the next mode when toggling the lyrics translation
+    pub fn next(self) -> Self {
+        match self {
+            Self::Original => Self::Translation,
+            Self::Translation => Self::Both,
+            Self::Both => Self::Original,
+        }
+    }
+}
+
 impl Default for UIState {
     fn default() -> Self {
         Self {
@@ -122,6 +143,8 @@ impl Default for UIState {
 
             count_prefix: None,
 
+            lyrics_translation_mode: LyricsTranslationMode::Original,
+
             #[cfg(feature = "image")]
             last_cover_image_render_info: ImageRenderInfo::default(),
         }
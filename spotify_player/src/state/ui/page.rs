@@ -1,4 +1,5 @@
 use crate::{
+    config,
     state::model::{Category, ContextId},
     ui::single_line_input::LineInput,
 };
@@ -23,6 +24,8 @@ pub enum PageState {
         track_uri: String,
         track: String,
         artists: String,
+        /// the cursor over the lyrics lines, unselected to follow the playback
+        line_list: ListState,
     },
     Browse {
         state: BrowsePageUIState,
@@ -51,6 +54,7 @@ pub struct LibraryPageUIState {
     pub playlist_list: ListState,
     pub saved_album_list: ListState,
     pub followed_artist_list: ListState,
+    pub local_item_list: ListState,
     pub focus: LibraryFocusState,
     pub playlist_folder_id: usize,
 }
@@ -99,6 +103,7 @@ pub enum LibraryFocusState {
     Playlists,
     SavedAlbums,
     FollowedArtists,
+    LocalFiles,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -172,6 +177,7 @@ impl PageState {
                         playlist_list,
                         saved_album_list,
                         followed_artist_list,
+                        local_item_list,
                         focus,
                         ..
                     },
@@ -181,6 +187,7 @@ impl PageState {
                 LibraryFocusState::FollowedArtists => {
                     MutableWindowState::List(followed_artist_list)
                 }
+                LibraryFocusState::LocalFiles => MutableWindowState::List(local_item_list),
             }),
             Self::Search {
                 state:
@@ -231,7 +238,7 @@ impl PageState {
                     Some(MutableWindowState::List(state))
                 }
             },
-            Self::Lyrics { .. } => None,
+            Self::Lyrics { line_list, .. } => Some(MutableWindowState::List(line_list)),
             Self::CommandHelp { scroll_offset } | Self::Queue { scroll_offset } => {
                 Some(MutableWindowState::Scroll(scroll_offset))
             }
@@ -245,6 +252,7 @@ impl LibraryPageUIState {
             playlist_list: ListState::default(),
             saved_album_list: ListState::default(),
             followed_artist_list: ListState::default(),
+            local_item_list: ListState::default(),
             focus: LibraryFocusState::Playlists,
             playlist_folder_id: 0,
         }
@@ -350,7 +358,19 @@ impl Focusable for PageState {
             Self::Library {
                 state: LibraryPageUIState { focus, .. },
                 ..
-            } => focus.next(),
+            } => {
+                focus.next();
+                // the local files window is only shown if the local library is configured
+                if *focus == LibraryFocusState::LocalFiles
+                    && config::get_config()
+                        .app_config
+                        .local_library
+                        .music_dirs
+                        .is_empty()
+                {
+                    focus.next();
+                }
+            }
             Self::Context {
                 state: Some(ContextPageUIState::Artist { focus, .. }),
                 ..
@@ -373,7 +393,19 @@ impl Focusable for PageState {
             Self::Library {
                 state: LibraryPageUIState { focus, .. },
                 ..
-            } => focus.previous(),
+            } => {
+                focus.previous();
+                // the local files window is only shown if the local library is configured
+                if *focus == LibraryFocusState::LocalFiles
+                    && config::get_config()
+                        .app_config
+                        .local_library
+                        .music_dirs
+                        .is_empty()
+                {
+                    focus.previous();
+                }
+            }
             Self::Context {
                 state: Some(ContextPageUIState::Artist { focus, .. }),
                 ..
@@ -414,7 +446,8 @@ impl_focusable!(
     LibraryFocusState,
     [Playlists, SavedAlbums],
     [SavedAlbums, FollowedArtists],
-    [FollowedArtists, Playlists]
+    [FollowedArtists, LocalFiles],
+    [LocalFiles, Playlists]
 );
 
 impl_focusable!(
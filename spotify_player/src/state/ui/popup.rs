@@ -22,6 +22,7 @@ pub enum PopupState {
     DeviceList(ListState),
     ArtistList(ArtistPopupAction, Vec<Artist>, ListState),
     ThemeList(Vec<crate::config::Theme>, ListState),
+    EqualizerPresetList(Vec<String>, ListState),
     ActionList(Box<ActionListItem>, ListState),
     PlaylistCreate {
         name: LineInput,
@@ -76,6 +77,7 @@ impl PopupState {
             | Self::UserSavedAlbumList(list_state)
             | Self::ArtistList(.., list_state)
             | Self::ThemeList(.., list_state)
+            | Self::EqualizerPresetList(.., list_state)
             | Self::ActionList(.., list_state) => Some(list_state),
             Self::Search { .. } | Self::PlaylistCreate { .. } => None,
         }
@@ -90,6 +92,7 @@ impl PopupState {
             | Self::UserSavedAlbumList(list_state)
             | Self::ArtistList(.., list_state)
             | Self::ThemeList(.., list_state)
+            | Self::EqualizerPresetList(.., list_state)
             | Self::ActionList(.., list_state) => Some(list_state),
             Self::Search { .. } | Self::PlaylistCreate { .. } => None,
         }
@@ -1,4 +1,8 @@
-use crate::{client::AppClient, config, state::SharedState};
+use crate::{
+    client::AppClient,
+    config,
+    state::{PlaybackEvent, SharedState},
+};
 use anyhow::Context;
 use librespot_connect::{ConnectConfig, Spirc};
 use librespot_core::authentication::Credentials;
@@ -211,12 +215,18 @@ pub async fn new_connection(
                                 if let Some(playback) = player.buffered_playback.as_mut() {
                                     playback.is_playing = true;
                                 }
+                                state
+                                    .events
+                                    .publish(PlaybackEvent::PlayPause { is_playing: true });
                             }
                             PlayerEvent::Paused { .. } => {
                                 let mut player = state.player.write();
                                 if let Some(playback) = player.buffered_playback.as_mut() {
                                     playback.is_playing = false;
                                 }
+                                state
+                                    .events
+                                    .publish(PlaybackEvent::PlayPause { is_playing: false });
                             }
                             _ => {}
                         }
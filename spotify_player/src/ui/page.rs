@@ -4,9 +4,12 @@ use std::{
 };
 
 use chrono_humanize::HumanTime;
-use ratatui::text::Line;
+use ratatui::text::{Line, Span};
 
-use crate::{state::Episode, utils::format_duration};
+use crate::{
+    state::{Episode, LocalLibraryItem, LyricsLine, LyricsTranslationMode},
+    utils::format_duration,
+};
 
 use super::{
     config, utils, utils::construct_and_render_block, Album, Artist, ArtistFocusState, Borders,
@@ -337,8 +340,7 @@ pub fn render_context_page(
                         let chunks = Layout::vertical([Constraint::Length(1), Constraint::Fill(0)])
                             .split(rect);
                         frame.render_widget(
-                            Paragraph::new(playlist.desc.to_string())
-                                .style(ui.theme.playlist_desc()),
+                            Paragraph::new(playlist.desc.clone()).style(ui.theme.playlist_desc()),
                             chunks[0],
                         );
                         chunks[1]
@@ -391,9 +393,19 @@ pub fn render_library_page(
     rect: Rect,
 ) {
     // 1. Get data
-    let curr_context_uri = state.player.read().playing_context_id().map(|c| c.uri());
+    let (curr_context_uri, curr_local_path) = {
+        let player = state.player.read();
+        (
+            player.playing_context_id().map(|c| c.uri()),
+            player
+                .local_playback
+                .as_ref()
+                .and_then(|p| p.track.local_path.clone()),
+        )
+    };
     let data = state.data.read();
     let configs = config::get_config();
+    let is_local_library_enabled = !configs.app_config.local_library.music_dirs.is_empty();
 
     let (focus_state, playlist_folder_id) = match ui.current_page() {
         PageState::Library { state } => (state.focus, state.playlist_folder_id),
@@ -405,6 +417,8 @@ pub fn render_library_page(
     // - a playlists window
     // - a saved albums window
     // - a followed artists window
+    // If the local library is configured, the followed artists window is split further
+    // to show a local files window.
 
     let chunks = ui
         .orientation
@@ -438,8 +452,29 @@ pub fn render_library_page(
         frame,
         chunks[1],
     );
-    let artist_rect =
-        construct_and_render_block("Artists", &ui.theme, Borders::ALL, frame, chunks[2]);
+    let (artist_rect, local_rect) = if is_local_library_enabled {
+        let chunks = ui
+            .orientation
+            .layout([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[2]);
+        let artist_rect = construct_and_render_block(
+            "Artists",
+            &ui.theme,
+            match ui.orientation {
+                Orientation::Horizontal => Borders::TOP | Borders::LEFT | Borders::BOTTOM,
+                Orientation::Vertical => Borders::ALL,
+            },
+            frame,
+            chunks[0],
+        );
+        let local_rect =
+            construct_and_render_block("Local Files", &ui.theme, Borders::ALL, frame, chunks[1]);
+        (artist_rect, Some(local_rect))
+    } else {
+        let artist_rect =
+            construct_and_render_block("Artists", &ui.theme, Borders::ALL, frame, chunks[2]);
+        (artist_rect, None)
+    };
 
     // 3. Construct the page's widgets
     // Construct the playlist window
@@ -457,9 +492,7 @@ pub fn render_library_page(
     let (playlist_list, n_playlists) = utils::construct_list_widget(
         &ui.theme,
         items,
-        is_active
-            && focus_state != LibraryFocusState::SavedAlbums
-            && focus_state != LibraryFocusState::FollowedArtists,
+        is_active && focus_state == LibraryFocusState::Playlists,
     );
     // Construct the saved album window
     let (album_list, n_albums) = utils::construct_list_widget(
@@ -479,6 +512,26 @@ pub fn render_library_page(
             .collect(),
         is_active && focus_state == LibraryFocusState::FollowedArtists,
     );
+    // Construct the local files window
+    let curr_local_track = curr_local_path
+        .as_ref()
+        .and_then(|path| data.local_library.index.track(path));
+    let (local_item_list, n_local_items) = utils::construct_list_widget(
+        &ui.theme,
+        ui.search_filtered_items(&data.local_library.items)
+            .into_iter()
+            .map(|item| {
+                let is_playing = curr_local_track.is_some_and(|t| match item {
+                    LocalLibraryItem::Album(album) => {
+                        t.album.as_ref().is_some_and(|a| a.id == album.id)
+                    }
+                    LocalLibraryItem::Artist(artist) => t.artists.iter().any(|a| a.id == artist.id),
+                });
+                (item.to_bidi_string(), is_playing)
+            })
+            .collect(),
+        is_active && focus_state == LibraryFocusState::LocalFiles,
+    );
 
     // 4. Render the page's widgets
     // Render the library page's windows.
@@ -508,6 +561,15 @@ pub fn render_library_page(
         n_artists,
         &mut page_state.followed_artist_list,
     );
+    if let Some(local_rect) = local_rect {
+        utils::render_list_window(
+            frame,
+            local_item_list,
+            local_rect,
+            n_local_items,
+            &mut page_state.local_item_list,
+        );
+    }
 }
 
 pub fn render_browse_page(
@@ -567,7 +629,7 @@ pub fn render_browse_page(
 }
 
 pub fn render_lyrics_page(
-    _is_active: bool,
+    is_active: bool,
     frame: &mut Frame,
     state: &SharedState,
     ui: &mut UIStateGuard,
@@ -578,22 +640,31 @@ pub fn render_lyrics_page(
 
     // 2. Construct the page's layout
     let rect = construct_and_render_block("Lyrics", &ui.theme, Borders::ALL, frame, rect);
-    let chunks = Layout::vertical([Constraint::Length(2), Constraint::Fill(0)]).split(rect);
+    let chunks = Layout::vertical([Constraint::Length(3), Constraint::Fill(0)]).split(rect);
 
     // 3. Construct the page's widgets
-    let Some(progress) = state.player.read().playback_progress() else {
-        frame.render_widget(Paragraph::new("No playback available"), rect);
-        return;
+    let (progress, duration) = {
+        let player = state.player.read();
+        let Some(progress) = player.current_track_progress() else {
+            frame.render_widget(Paragraph::new("No playback available"), rect);
+            return;
+        };
+        let duration = player
+            .current_track()
+            .and_then(|track| chrono::Duration::from_std(track.duration).ok());
+        (progress, duration)
     };
 
     let PageState::Lyrics {
         track_uri,
         track,
         artists,
-    } = ui.current_page_mut()
+        line_list,
+    } = ui.current_page()
     else {
         return;
     };
+    let cursor = line_list.selected();
 
     let lyrics = match data.caches.lyrics.get(track_uri) {
         None => {
@@ -611,40 +682,176 @@ pub fn render_lyrics_page(
     // render lyric page description text
     let bidi_track = to_bidi_string(track);
     let bidi_artists = to_bidi_string(artists);
+    let lyrics_offset = data.lyrics_offset(track_uri);
+    let source = if !lyrics.synced {
+        format!("Lyrics from {} (unsynced)", lyrics.source)
+    } else if lyrics_offset != 0 {
+        format!(
+            "Lyrics from {} (offset {:+.1}s)",
+            lyrics.source,
+            lyrics_offset as f64 / 1000.0
+        )
+    } else {
+        format!("Lyrics from {}", lyrics.source)
+    };
     frame.render_widget(
-        Paragraph::new(format!("{bidi_track} by {bidi_artists}")).style(ui.theme.page_desc()),
+        Paragraph::new(vec![
+            Line::raw(format!("{bidi_track} by {bidi_artists}")),
+            Line::raw(source),
+        ])
+        .style(ui.theme.page_desc()),
         chunks[0],
     );
 
     // render lyric text
+    let translation_mode = if lyrics.has_translation() {
+        ui.lyrics_translation_mode
+    } else {
+        LyricsTranslationMode::Original
+    };
+    // lines without a translation are shown in their original text when showing only the translation
+    let translation_text = |line: &LyricsLine| {
+        let text = match (&line.translation, translation_mode) {
+            (Some(translation), _) => translation,
+            (None, LyricsTranslationMode::Both) => "",
+            (None, _) => &line.text,
+        };
+        to_bidi_string(text)
+    };
+
+    if !lyrics.synced {
+        // without timestamps, scroll the lyrics proportionally to the playback progress
+        let lines = lyrics
+            .lines
+            .iter()
+            .map(|line| Line::raw(to_bidi_string(&line.text)))
+            .collect();
+        let translation_lines = lyrics
+            .lines
+            .iter()
+            .map(|line| Line::raw(translation_text(line)))
+            .collect();
+        let max_offset = lyrics.lines.len().saturating_sub(chunks[1].height as usize);
+        let offset = match duration {
+            Some(duration) if duration > chrono::Duration::zero() => {
+                let ratio = progress.num_milliseconds() as f64 / duration.num_milliseconds() as f64;
+                (ratio.clamp(0.0, 1.0) * max_offset as f64) as u16
+            }
+            _ => 0,
+        };
+        render_lyrics_text(
+            frame,
+            chunks[1],
+            translation_mode,
+            lines,
+            translation_lines,
+            offset,
+        );
+        return;
+    }
+
+    // a positive offset shows the lyrics sooner
+    let position = progress + chrono::Duration::milliseconds(lyrics_offset);
 
     // the last played line id (1-based)
     // zero value indicates no line has been played yet
-    let mut last_played_line_id = 0;
-    for (id, (t, _)) in lyrics.lines.iter().enumerate() {
-        if *t <= progress {
-            last_played_line_id = id + 1;
-        }
-    }
-    let lines = lyrics
-        .lines
-        .iter()
-        .enumerate()
-        .map(|(id, (_, line))| match (id + 1).cmp(&last_played_line_id) {
-            std::cmp::Ordering::Less => Line::styled(line, ui.theme.lyrics_played()),
-            std::cmp::Ordering::Equal => Line::styled(line, ui.theme.lyrics_playing()),
-            std::cmp::Ordering::Greater => Line::raw(line),
+    let last_played_line_id = lyrics.playing_line(position).map_or(0, |id| id + 1);
+    // lines possibly filtered by a search query, with their ids
+    let shown_lines = ui
+        .search_filtered_items(&lyrics.lines)
+        .into_iter()
+        .map(|line| {
+            let id = lyrics
+                .lines
+                .iter()
+                .position(|l| std::ptr::eq(l, line))
+                .unwrap_or_default();
+            (id, line)
         })
         .collect::<Vec<_>>();
 
-    let mut paragraph = Paragraph::new(lines);
-    // keep the currently playing line in the center if
+    let mut lines = vec![];
+    let mut translation_lines = vec![];
+    for (i, (id, line)) in shown_lines.iter().enumerate() {
+        let style = match (id + 1).cmp(&last_played_line_id) {
+            std::cmp::Ordering::Less => ui.theme.lyrics_played(),
+            std::cmp::Ordering::Equal => ui.theme.lyrics_playing(),
+            std::cmp::Ordering::Greater => Style::default(),
+        };
+        let style = if cursor == Some(i) {
+            style.patch(ui.theme.selection(is_active))
+        } else {
+            style
+        };
+
+        let text_line = if id + 1 == last_played_line_id && !line.words.is_empty() {
+            // highlight the sung words of the playing line
+            let spans = line.words.iter().map(|(t, word)| {
+                let word_style = if *t <= position {
+                    style.patch(ui.theme.lyrics_playing_word())
+                } else {
+                    style
+                };
+                Span::styled(to_bidi_string(word), word_style)
+            });
+            Line::from(spans.collect::<Vec<_>>())
+        } else {
+            Line::styled(to_bidi_string(&line.text), style)
+        };
+        lines.push(text_line);
+        translation_lines.push(Line::styled(translation_text(line), style));
+    }
+
+    // keep the cursor's line, or the currently playing line, in the center if
     // the line goes pass the lower half of lyrics section
+    let centered_line_id = match cursor {
+        Some(i) => i + 1,
+        None => shown_lines
+            .iter()
+            .rposition(|(id, _)| *id < last_played_line_id)
+            .map_or(0, |i| i + 1),
+    };
     let half_height = (chunks[1].height / 2) as usize;
-    if let Some(offset) = last_played_line_id.checked_sub(half_height) {
-        paragraph = paragraph.scroll((offset as u16, 0));
+    let offset = centered_line_id.saturating_sub(half_height);
+    render_lyrics_text(
+        frame,
+        chunks[1],
+        translation_mode,
+        lines,
+        translation_lines,
+        offset as u16,
+    );
+}
+
+/// Render lyrics lines and their translation based on the lyrics translation mode.
+///
+/// Lines and their translation are shown side by side in the `Both` mode.
+fn render_lyrics_text(
+    frame: &mut Frame,
+    rect: Rect,
+    mode: LyricsTranslationMode,
+    lines: Vec<Line<'static>>,
+    translation_lines: Vec<Line<'static>>,
+    offset: u16,
+) {
+    match mode {
+        LyricsTranslationMode::Original => {
+            frame.render_widget(Paragraph::new(lines).scroll((offset, 0)), rect);
+        }
+        LyricsTranslationMode::Translation => {
+            frame.render_widget(Paragraph::new(translation_lines).scroll((offset, 0)), rect);
+        }
+        LyricsTranslationMode::Both => {
+            let chunks =
+                Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(rect);
+            frame.render_widget(Paragraph::new(lines).scroll((offset, 0)), chunks[0]);
+            frame.render_widget(
+                Paragraph::new(translation_lines).scroll((offset, 0)),
+                chunks[1],
+            );
+        }
     }
-    frame.render_widget(paragraph, chunks[1]);
 }
 
 pub fn render_commands_help_page(frame: &mut Frame, ui: &mut UIStateGuard, rect: Rect) {
@@ -727,7 +934,7 @@ pub fn render_queue_page(
     fn get_playable_name(item: &PlayableItem) -> String {
         match item {
             PlayableItem::Track(FullTrack { ref name, .. })
-            | PlayableItem::Episode(FullEpisode { ref name, .. }) => name.to_string(),
+            | PlayableItem::Episode(FullEpisode { ref name, .. }) => name.clone(),
             PlayableItem::Unknown(_) => String::new(),
         }
     }
@@ -949,7 +1156,15 @@ fn render_track_table(
     // get the current playing track's URI to decorate such track (if exists) in the track table
     let mut playing_track_uri = String::new();
     let mut playing_id = "";
-    if let Some(ref playback) = state.player.read().playback {
+    let player = state.player.read();
+    if let Some(ref playback) = player.local_playback {
+        playing_track_uri = playback.track.id.uri();
+        playing_id = if playback.is_playing {
+            &configs.app_config.play_icon
+        } else {
+            &configs.app_config.pause_icon
+        };
+    } else if let Some(ref playback) = player.playback {
         if let Some(rspotify::model::PlayableItem::Track(ref track)) = playback.item {
             playing_track_uri = track
                 .id
@@ -964,6 +1179,7 @@ fn render_track_table(
             };
         }
     }
+    drop(player);
 
     // enable Added column if any track in the table has added_at field specified
     let added_at_enabled = tracks.iter().any(|t| t.added_at > 0);
@@ -979,7 +1195,9 @@ fn render_track_table(
                 ((id + 1).to_string(), Style::default())
             };
             Row::new(vec![
-                if data.user_data.is_liked_track(t) {
+                if t.is_local {
+                    Cell::from(&configs.app_config.local_icon as &str)
+                } else if data.user_data.is_liked_track(t) {
                     Cell::from(&configs.app_config.liked_icon as &str).style(ui.theme.like())
                 } else {
                     Cell::from("")
@@ -1013,7 +1231,14 @@ fn render_track_table(
     let track_table = Table::new(
         rows,
         [
-            Constraint::Length(configs.app_config.liked_icon.chars().count() as u16),
+            Constraint::Length(
+                configs
+                    .app_config
+                    .liked_icon
+                    .chars()
+                    .count()
+                    .max(configs.app_config.local_icon.chars().count()) as u16,
+            ),
             Constraint::Length(4),
             Constraint::Fill(4),
             Constraint::Fill(3),
@@ -5,6 +5,7 @@ use super::{
 };
 #[cfg(feature = "image")]
 use crate::state::ImageRenderInfo;
+use crate::state::LocalPlayback;
 use crate::ui::utils::{format_genres, to_bidi_string};
 #[cfg(feature = "image")]
 use anyhow::{Context, Result};
@@ -25,6 +26,24 @@ pub fn render_playback_window(
     let rect = construct_and_render_block("Playback", &ui.theme, Borders::ALL, frame, rect);
 
     let player = state.player.read();
+
+    // the local player takes over the playback window while it has a playback
+    if let Some(ref playback) = player.local_playback {
+        #[cfg(feature = "image")]
+        clear_cover_image(frame, ui);
+
+        let (metadata_rect, progress_bar_rect) = split_rect_for_progress_bar(rect);
+        let playback_text = construct_local_playback_text(ui, playback);
+        frame.render_widget(Paragraph::new(playback_text), metadata_rect);
+
+        if !playback.track.duration.is_zero() {
+            let duration = chrono::Duration::from_std(playback.track.duration).unwrap_or_default();
+            let progress = chrono::Duration::from_std(playback.progress()).unwrap_or_default();
+            render_playback_progress_bar(frame, ui, progress, duration, progress_bar_rect);
+        }
+        return other_rect;
+    }
+
     if let Some(ref playback) = player.playback {
         if let Some(item) = &playback.item {
             let (metadata_rect, progress_bar_rect) = {
@@ -140,16 +159,7 @@ pub fn render_playback_window(
     // Previously rendered image can result in a weird rendering text,
     // clear the previous widget's area before rendering the text.
     #[cfg(feature = "image")]
-    {
-        if ui.last_cover_image_render_info.rendered {
-            clear_area(
-                frame,
-                ui.last_cover_image_render_info.render_area,
-                &ui.theme,
-            );
-            ui.last_cover_image_render_info = ImageRenderInfo::default();
-        }
-    }
+    clear_cover_image(frame, ui);
 
     frame.render_widget(
             Paragraph::new(
@@ -186,6 +196,18 @@ fn split_rect_for_cover_img(rect: Rect) -> (Rect, Rect) {
     (ver_chunks[0], hor_chunks[1])
 }
 
+#[cfg(feature = "image")]
+fn clear_cover_image(frame: &mut Frame, ui: &mut UIStateGuard) {
+    if ui.last_cover_image_render_info.rendered {
+        clear_area(
+            frame,
+            ui.last_cover_image_render_info.render_area,
+            &ui.theme,
+        );
+        ui.last_cover_image_render_info = ImageRenderInfo::default();
+    }
+}
+
 #[cfg(feature = "image")]
 fn clear_area(frame: &mut Frame, rect: Rect, theme: &config::Theme) {
     for x in rect.left()..rect.right() {
@@ -206,35 +228,11 @@ fn construct_playback_text(
     playable: &rspotify::model::PlayableItem,
     playback: &PlaybackMetadata,
 ) -> Text<'static> {
-    // Construct a "styled" text (`playback_text`) from playback's data
-    // based on a user-configurable format string (app_config.playback_format)
     let configs = config::get_config();
-    let format_str = &configs.app_config.playback_format;
     let data = state.data.read();
 
-    let mut playback_text = Text::default();
-    let mut spans = vec![];
-
-    // this regex is to handle a format argument or a newline
-    let re = regex::Regex::new(r"\{.*?\}|\n").unwrap();
-
-    let mut ptr = 0;
-    for m in re.find_iter(format_str) {
-        let s = m.start();
-        let e = m.end();
-        if ptr < s {
-            spans.push(Span::raw(format_str[ptr..s].to_string()));
-        }
-        ptr = e;
-
-        let (text, style) = match m.as_str() {
-            // upon encountering a newline, create a new `Spans`
-            "\n" => {
-                let mut tmp = vec![];
-                std::mem::swap(&mut tmp, &mut spans);
-                playback_text.lines.push(Line::from(tmp));
-                continue;
-            }
+    format_playback_text(|arg| {
+        Some(match arg {
             "{status}" => (
                 if playback.is_playing {
                     &configs.app_config.play_icon
@@ -246,17 +244,13 @@ fn construct_playback_text(
             ),
             "{liked}" => match playable {
                 rspotify::model::PlayableItem::Track(track) => match &track.id {
-                    Some(id) => {
-                        if data.user_data.saved_tracks.contains_key(&id.uri()) {
-                            (configs.app_config.liked_icon.clone(), ui.theme.like())
-                        } else {
-                            continue;
-                        }
+                    Some(id) if data.user_data.saved_tracks.contains_key(&id.uri()) => {
+                        (configs.app_config.liked_icon.clone(), ui.theme.like())
                     }
-                    None => continue,
+                    _ => return None,
                 },
                 rspotify::model::PlayableItem::Episode(_)
-                | rspotify::model::PlayableItem::Unknown(_) => continue,
+                | rspotify::model::PlayableItem::Unknown(_) => return None,
             },
             "{track}" => match playable {
                 rspotify::model::PlayableItem::Track(track) => (
@@ -282,7 +276,7 @@ fn construct_playback_text(
                     ui.theme.playback_track(),
                 ),
                 rspotify::model::PlayableItem::Unknown(_) => {
-                    continue;
+                    return None;
                 }
             },
             "{artists}" => match playable {
@@ -294,7 +288,7 @@ fn construct_playback_text(
                     (episode.show.publisher.clone(), ui.theme.playback_artists())
                 }
                 rspotify::model::PlayableItem::Unknown(_) => {
-                    continue;
+                    return None;
                 }
             },
             "{album}" => match playable {
@@ -306,7 +300,7 @@ fn construct_playback_text(
                     ui.theme.playback_album(),
                 ),
                 rspotify::model::PlayableItem::Unknown(_) => {
-                    continue;
+                    return None;
                 }
             },
             "{genres}" => match playable {
@@ -321,7 +315,7 @@ fn construct_playback_text(
                     (to_bidi_string("no genre"), ui.theme.playback_genres())
                 }
                 rspotify::model::PlayableItem::Unknown(_) => {
-                    continue;
+                    return None;
                 }
             },
             "{metadata}" => {
@@ -352,10 +346,86 @@ fn construct_playback_text(
                 let metadata_str = parts.join(" | ");
                 (metadata_str, ui.theme.playback_metadata())
             }
-            _ => continue,
-        };
+            _ => return None,
+        })
+    })
+}
 
-        spans.push(Span::styled(text, style));
+fn construct_local_playback_text(ui: &UIStateGuard, playback: &LocalPlayback) -> Text<'static> {
+    let configs = config::get_config();
+    let track = &playback.track;
+
+    format_playback_text(|arg| {
+        Some(match arg {
+            "{status}" => (
+                if playback.is_playing {
+                    &configs.app_config.play_icon
+                } else {
+                    &configs.app_config.pause_icon
+                }
+                .to_owned(),
+                ui.theme.playback_status(),
+            ),
+            "{track}" => (to_bidi_string(&track.name), ui.theme.playback_track()),
+            "{artists}" => (
+                to_bidi_string(&track.artists_info()),
+                ui.theme.playback_artists(),
+            ),
+            "{album}" => (
+                to_bidi_string(&track.album_info()),
+                ui.theme.playback_album(),
+            ),
+            "{metadata}" => {
+                let mut parts = vec![];
+                for field in &configs.app_config.playback_metadata_fields {
+                    match field.as_str() {
+                        "volume" => parts.push(format!("volume: {}%", playback.volume)),
+                        "device" => parts.push("device: local".to_string()),
+                        _ => {}
+                    }
+                }
+                (parts.join(" | "), ui.theme.playback_metadata())
+            }
+            _ => return None,
+        })
+    })
+}
+
+/// Construct a "styled" text from the playback's data based on
+/// a user-configurable format string (`app_config.playback_format`).
+///
+/// `format_arg` returns the text and style of a format argument, or `None` if the argument should be skipped.
+fn format_playback_text(
+    mut format_arg: impl FnMut(&str) -> Option<(String, Style)>,
+) -> Text<'static> {
+    let format_str = &config::get_config().app_config.playback_format;
+
+    let mut playback_text = Text::default();
+    let mut spans = vec![];
+
+    // this regex is to handle a format argument or a newline
+    let re = regex::Regex::new(r"\{.*?\}|\n").unwrap();
+
+    let mut ptr = 0;
+    for m in re.find_iter(format_str) {
+        let s = m.start();
+        let e = m.end();
+        if ptr < s {
+            spans.push(Span::raw(format_str[ptr..s].to_string()));
+        }
+        ptr = e;
+
+        // upon encountering a newline, create a new `Spans`
+        if m.as_str() == "\n" {
+            let mut tmp = vec![];
+            std::mem::swap(&mut tmp, &mut spans);
+            playback_text.lines.push(Line::from(tmp));
+            continue;
+        }
+
+        if let Some((text, style)) = format_arg(m.as_str()) {
+            spans.push(Span::styled(text, style));
+        }
     }
     if ptr < format_str.len() {
         spans.push(Span::raw(format_str[ptr..].to_string()));
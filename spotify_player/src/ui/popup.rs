@@ -109,6 +109,16 @@ pub fn render_popup(
                 let rect = render_list_popup(frame, rect, "Themes", items, 7, ui);
                 (rect, false)
             }
+            PopupState::EqualizerPresetList(presets, ..) => {
+                let current_preset = state.player.read().equalizer_preset.clone();
+                let items = presets
+                    .iter()
+                    .map(|name| (name.clone(), *name == current_preset))
+                    .collect();
+
+                let rect = render_list_popup(frame, rect, "Equalizer Presets", items, 7, ui);
+                (rect, false)
+            }
             PopupState::UserPlaylistList(action, _) => {
                 let data = state.data.read();
                 let (items, search_query) = match action {